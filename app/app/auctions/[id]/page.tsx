@@ -254,14 +254,15 @@ export default function AuctionDetailPage({ params }: { params: Promise<{ id: st
         try {
             const program = getProgram(connection, wallet);
             const auctionPubkey = new PublicKey(id);
+            const [auctionResultPda] = new ArciumService(connection, wallet).getAuctionResultPDA(auctionPubkey);
 
-
-
+            // The revealed result is checked for defaulters; expired auctions never revealed one
             const ix = await program.methods
                 .refundLoser()
                 .accountsPartial({
                     bidder: wallet.publicKey,
                     auction: auctionPubkey,
+                    auctionResult: auctionData?.expired ? null : auctionResultPda,
                 })
                 .instruction();
 
@@ -353,7 +354,7 @@ export default function AuctionDetailPage({ params }: { params: Promise<{ id: st
 
                 // Self-referential PDA seeds require explicit account - bypass TS
                 ix = await program.methods
-                    .finalizeNoWinner()
+                    .finalizeNoWinner(null)
                     .accountsPartial({
                         payer: wallet.publicKey,
                         seller: wallet.publicKey,
@@ -367,7 +368,7 @@ export default function AuctionDetailPage({ params }: { params: Promise<{ id: st
             } else if (auctionEnded && !hasBids) {
                 // Auction ended with zero bids - use reclaim_unsold
                 ix = await program.methods
-                    .reclaimUnsold()
+                    .reclaimUnsold(null)
                     .accountsPartial({
                         seller: wallet.publicKey,
                         auction: auctionPubkey,
@@ -378,7 +379,7 @@ export default function AuctionDetailPage({ params }: { params: Promise<{ id: st
             } else {
                 // Auction not ended - use cancel_auction
                 ix = await program.methods
-                    .cancelAuction()
+                    .cancelAuction(null)
                     .accountsPartial({
                         seller: wallet.publicKey,
                         auction: auctionPubkey,
@@ -568,7 +569,7 @@ export default function AuctionDetailPage({ params }: { params: Promise<{ id: st
                 }
 
                 const finalizeIx = await program.methods
-                    .finalizeNoWinner()
+                    .finalizeNoWinner(null)
                     .accountsPartial({
                         payer: wallet.publicKey,
                         seller: seller,
@@ -640,7 +641,7 @@ export default function AuctionDetailPage({ params }: { params: Promise<{ id: st
                 }
 
                 const settleIx = await program.methods
-                    .settleAuction()
+                    .settleAuction(null)
                     .accountsPartial({
                         seller: new PublicKey(auctionData.seller),
                         winner: winner,
@@ -7,10 +7,11 @@ import { Button } from "@/components/ui/button";
 import { DatePicker } from "@/components/ui/DatePicker";
 import { useConnection, useWallet, useAnchorWallet } from "@solana/wallet-adapter-react";
 import { WalletMultiButton } from "@solana/wallet-adapter-react-ui";
-import { Keypair, SystemProgram, PublicKey } from "@solana/web3.js";
+import { SystemProgram, PublicKey } from "@solana/web3.js";
 import { BN } from "@coral-xyz/anchor";
-import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { getProgram } from "@/utils/anchor";
+import { getAuctionPDA, newListingId } from "@/utils/pda";
 
 // Metaplex Imports
 import { createUmi } from '@metaplex-foundation/umi-bundle-defaults';
@@ -150,14 +151,12 @@ export default function CreateAuctionPage() {
             const nftMint = new PublicKey(selectedNft.mint);
             const sellerNftAccount = new PublicKey(selectedNft.tokenAccount);
 
-            // 1. Derive Auction PDA
-            const [auctionPda] = PublicKey.findProgramAddressSync(
-                [Buffer.from("auction"), nftMint.toBuffer()],
-                program.programId
-            );
+            // 1. Derive Auction PDA (a new listing id per auction of this mint)
+            const listingId = newListingId();
+            const [auctionPda] = getAuctionPDA(nftMint, listingId, program.programId);
 
-            // 2. Generate Escrow Keypair
-            const nftEscrowKeypair = Keypair.generate();
+            // 2. Derive the escrow: the auction PDA's ATA for the NFT
+            const nftEscrow = getAssociatedTokenAddressSync(nftMint, auctionPda, true);
 
             // 3. Prepare Args
             const minPriceLamports = new BN(parseFloat(minimumPrice) * 1_000_000_000);
@@ -168,20 +167,20 @@ export default function CreateAuctionPage() {
 
             console.log("Creating auction...", {
                 auctionPda: auctionPda.toBase58(),
-                nftEscrow: nftEscrowKeypair.publicKey.toBase58(),
+                nftEscrow: nftEscrow.toBase58(),
                 mint: nftMint.toBase58()
             });
 
             // 4. Send Transaction
             const tx = await program.methods
-                .createAuction(minPriceLamports, endTime)
+                .createAuction(listingId, minPriceLamports, new BN(0), endTime, { firstPrice: {} }, false, new BN(86_400), null, null)
                 .accounts({
                     seller: wallet.publicKey,
+                    auction: auctionPda,
                     nftMint: nftMint,
                     sellerNftAccount: sellerNftAccount,
-                    nftEscrowAccount: nftEscrowKeypair.publicKey,
+                    nftEscrowAccount: nftEscrow,
                 })
-                .signers([nftEscrowKeypair]) // Sign with the new keypair for init
                 .rpc();
 
             console.log("Auction Created! Tx:", tx);
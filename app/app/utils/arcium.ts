@@ -50,6 +50,9 @@ export const ARCIUM_CONFIG = {
 
 // Seeds
 const AUCTION_STATE_SEED = "auction_bid_state";
+// Mirrors AUCTION_STATE_CIPHERTEXT_COUNT: 8 ranked (amount, bidder_0..3, locked_slot)
+// slots, the reserve, then 17 defaulter ciphertexts
+const AUCTION_STATE_CIPHERTEXT_COUNT = 8 * 6 + 1 + 17;
 const AUCTION_RESULT_SEED = "auction_result";
 
 
@@ -135,7 +138,8 @@ export class ArciumService {
             // Skip 8-byte discriminator
             const offset = 8;
 
-            // Parse manually: pubkey(32) + u8(1) + u64(8) + 5*[u8;32] + u128(16)
+            // Parse manually: pubkey(32) + u8(1) + u64(8) + COUNT*[u8;32] + u128(16)
+            // The leading ranked slot holds the current leader's amount and bidder words
             const auctionIdBytes = data.slice(offset, offset + 32);
             const bump = data[offset + 32];
             const bidCount = data.readBigUInt64LE(offset + 33);
@@ -145,8 +149,9 @@ export class ArciumService {
             const encryptedWinner2 = Array.from(data.slice(offset + 137, offset + 169));
             const encryptedWinner3 = Array.from(data.slice(offset + 169, offset + 201));
             // u128 nonce (16 bytes)
-            const nonceLow = data.readBigUInt64LE(offset + 201);
-            const nonceHigh = data.readBigUInt64LE(offset + 209);
+            const nonceOffset = offset + 41 + 32 * AUCTION_STATE_CIPHERTEXT_COUNT;
+            const nonceLow = data.readBigUInt64LE(nonceOffset);
+            const nonceHigh = data.readBigUInt64LE(nonceOffset + 8);
             const nonce = nonceLow + (nonceHigh << BigInt(64));
 
             return {
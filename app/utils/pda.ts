@@ -0,0 +1,19 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { PROGRAM_ID } from "@/utils/anchor";
+
+// Auction PDA seeds: ["auction", nft_mint, listing_id (u64 LE)]
+// The listing id lets the same NFT be auctioned again while old auctions stay on-chain.
+export const getAuctionPDA = (
+    nftMint: PublicKey,
+    listingId: BN,
+    programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] => {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from("auction"), nftMint.toBuffer(), listingId.toArrayLike(Buffer, "le", 8)],
+        programId
+    );
+};
+
+// Millisecond timestamps are unique enough per seller; a collision just fails the init
+export const newListingId = (): BN => new BN(Date.now());
@@ -4,70 +4,396 @@ use arcis::*;
 mod circuits {
     use arcis::*;
 
+    /// Supplied by the bidder, encrypted with their shared key
     pub struct BidInputs {
-        current_max: u64,
-        
-        // Flattened Pubkey (32 bytes = 4 * u64)
-        current_winner_0: u64,
-        current_winner_1: u64,
-        current_winner_2: u64,
-        current_winner_3: u64,
-        
         new_bid: u64,
-        
-        // Flattened New Bidder Pubkey
-        new_bidder_0: u64,
-        new_bidder_1: u64,
-        new_bidder_2: u64,
-        new_bidder_3: u64,
-        
-        min_price: u64,
     }
 
+    /// Units a multi-unit auction can sell (must match sealed_auction's MAX_UNITS)
+    const MAX_UNITS: usize = 4;
+    /// Top bids kept per auction, highest first. Multi-unit auctions need one per
+    /// unit plus the highest losing bid, which prices second-price (uniform)
+    /// auctions. Single-unit auctions keep the rest so that a retracted leader
+    /// is replaced by the next real bid rather than by the reserve.
+    /// (must match arcium_program's RANKED_BIDS)
+    const RANKED_BIDS: usize = 8;
+
+    /// Flattened Pubkey (32 bytes = 4 * u64)
+    #[derive(Copy, Clone)]
+    pub struct Bidder {
+        w0: u64,
+        w1: u64,
+        w2: u64,
+        w3: u64,
+    }
+
+    /// Most over-collateral bidders tracked per auction
+    /// (must match sealed_auction's MAX_DEFAULTERS)
+    const MAX_DEFAULTERS: usize = 4;
+
+    /// Bidders caught bidding above their locked collateral, in the order they
+    /// were caught; once full, later ones are not tracked
+    #[derive(Copy, Clone)]
+    pub struct Defaulters {
+        count: u64,
+        bidders: [Bidder; MAX_DEFAULTERS],
+    }
+
+    /// One BidEscrow's bid. A bidder and the escrow's lock slot identify the
+    /// escrow, so a bidder who withdraws and locks again gets a new slot.
+    #[derive(Copy, Clone)]
+    pub struct BidSlot {
+        amount: u64,
+        bidder: Bidder,
+        // Lock slot of the bidder's BidEscrow, ranking equal amounts
+        locked_slot: u64,
+    }
+
+    /// Running auction state, owned by the MXE and stored in AuctionBidState
+    /// (or MultiUnitBidState). Bids are ordered by ranks_above, with at most
+    /// one slot per BidEscrow. Empty slots have amount 0.
     pub struct AuctionState {
+        ranked: [BidSlot; RANKED_BIDS],
+        // Reserve price set once by the seller; never revealed on its own
+        reserve: u64,
+        // Bidders who bid above their locked collateral, revealed for slashing
+        defaulters: Defaulters,
+    }
+
+    /// What reveal_winner makes public: the reserve stays encrypted
+    pub struct AuctionOutcome {
         max_bid: u64,
+        second_bid: u64,
         winner_0: u64,
         winner_1: u64,
         winner_2: u64,
         winner_3: u64,
+        defaulters: Defaulters,
     }
 
-    #[instruction]
-    pub fn submit_bid(input_ctxt: Enc<Shared, BidInputs>) -> Enc<Shared, AuctionState> {
-        let input = input_ctxt.to_arcis();
-        
-        // 1. Check conditions
-        let is_valid_amount = input.new_bid >= input.min_price;
-        let is_higher = input.new_bid > input.current_max;
-        
-        let should_switch = is_valid_amount && is_higher;
-        
-        // 2. Select new or old values
-        // If switching, take NEW values. Else keep OLD.
-        let output = if should_switch {
-            AuctionState {
-                max_bid: input.new_bid,
-                winner_0: input.new_bidder_0,
-                winner_1: input.new_bidder_1,
-                winner_2: input.new_bidder_2,
-                winner_3: input.new_bidder_3,
+    /// What reveal_multi_unit makes public: the uniform price and who won,
+    /// never the individual bids
+    pub struct MultiUnitOutcome {
+        clearing_price: u64,
+        winner_count: u64,
+        winners: [Bidder; MAX_UNITS],
+        defaulters: Defaulters,
+    }
+
+    fn same_bidder(a: Bidder, b: Bidder) -> bool {
+        a.w0 == b.w0 && a.w1 == b.w1 && a.w2 == b.w2 && a.w3 == b.w3
+    }
+
+    /// Whether two slots hold the bid of the same BidEscrow
+    fn same_escrow(a: BidSlot, b: BidSlot) -> bool {
+        same_bidder(a.bidder, b.bidder) && a.locked_slot == b.locked_slot
+    }
+
+    /// Final tie-break between escrows locked in the same slot: the lower
+    /// bidder pubkey, comparing the little-endian words w0 to w3 in turn
+    fn bidder_below(a: Bidder, b: Bidder) -> bool {
+        a.w0 < b.w0
+            || (a.w0 == b.w0 && a.w1 < b.w1)
+            || (a.w0 == b.w0 && a.w1 == b.w1 && a.w2 < b.w2)
+            || (a.w0 == b.w0 && a.w1 == b.w1 && a.w2 == b.w2 && a.w3 < b.w3)
+    }
+
+    /// Ranking order: higher amount first, then the earlier lock slot, then
+    /// the lower bidder pubkey
+    fn ranks_above(a: BidSlot, b: BidSlot) -> bool {
+        a.amount > b.amount
+            || (a.amount == b.amount && a.locked_slot < b.locked_slot)
+            || (a.amount == b.amount && a.locked_slot == b.locked_slot && bidder_below(a.bidder, b.bidder))
+    }
+
+    fn empty_slot() -> BidSlot {
+        BidSlot {
+            amount: 0,
+            bidder: Bidder { w0: 0, w1: 0, w2: 0, w3: 0 },
+            locked_slot: 0,
+        }
+    }
+
+    fn no_defaulters() -> Defaulters {
+        Defaulters {
+            count: 0,
+            bidders: [Bidder { w0: 0, w1: 0, w2: 0, w3: 0 }; MAX_DEFAULTERS],
+        }
+    }
+
+    /// Adds `bidder` to the list if `over_collateral`, once per bidder and
+    /// only while there is room
+    fn record_defaulter(list: Defaulters, bidder: Bidder, over_collateral: bool) -> Defaulters {
+        let mut known = false;
+        for i in 0..MAX_DEFAULTERS {
+            if (i as u64) < list.count && same_bidder(list.bidders[i], bidder) {
+                known = true;
             }
-        } else {
-            AuctionState {
-                max_bid: input.current_max,
-                winner_0: input.current_winner_0,
-                winner_1: input.current_winner_1,
-                winner_2: input.current_winner_2,
-                winner_3: input.current_winner_3,
+        }
+        let record = over_collateral && !known;
+
+        let mut out = list;
+        for i in 0..MAX_DEFAULTERS {
+            if record && (i as u64) == list.count {
+                out.bidders[i] = bidder;
+            }
+        }
+        if record && list.count < MAX_DEFAULTERS as u64 {
+            out.count = list.count + 1;
+        }
+        out
+    }
+
+    /// The state every auction starts from. Hidden-reserve auctions take the
+    /// seller's encrypted reserve, all others use the public min_price.
+    fn empty_state(hidden: u64, min_price: u64, hidden_reserve: bool) -> AuctionState {
+        AuctionState {
+            ranked: [empty_slot(); RANKED_BIDS],
+            reserve: if hidden_reserve { hidden } else { min_price },
+            defaulters: no_defaulters(),
+        }
+    }
+
+    /// Clears the slot of `escrow`, if ranked, and moves the bids below it up.
+    /// Only one slot can match, so one pass moves the gap to the end.
+    fn remove_escrow(ranked: [BidSlot; RANKED_BIDS], escrow: BidSlot, remove: bool) -> [BidSlot; RANKED_BIDS] {
+        let mut out = ranked;
+        for i in 0..RANKED_BIDS {
+            if remove && same_escrow(out[i], escrow) {
+                out[i] = empty_slot();
+            }
+        }
+        for i in 0..RANKED_BIDS - 1 {
+            let current = out[i];
+            let next = out[i + 1];
+            if current.amount < next.amount {
+                out[i] = next;
+                out[i + 1] = current;
+            }
+        }
+        out
+    }
+
+    /// Applies one bid to the ranking. An escrow holds at most one slot: a
+    /// higher bid replaces its earlier one, a lower one is ignored. Bids below
+    /// the reserve or above the locked collateral are dropped privately; the
+    /// latter are recorded as defaulters.
+    fn apply_bid(state: AuctionState, bid: BidSlot, max_collateral: u64) -> AuctionState {
+        let is_valid_amount = bid.amount > 0
+            && bid.amount >= state.reserve
+            && bid.amount <= max_collateral;
+        let mut previous_bid = 0;
+        for i in 0..RANKED_BIDS {
+            if same_escrow(state.ranked[i], bid) {
+                previous_bid = state.ranked[i].amount;
+            }
+        }
+        let should_insert = is_valid_amount && bid.amount > previous_bid;
+
+        // 1. Drop the escrow's earlier slot
+        let mut ranked = remove_escrow(state.ranked, bid, should_insert);
+
+        // 2. Insert, carrying displaced slots down; the lowest one falls off
+        let mut carry = bid;
+        if !should_insert {
+            carry = empty_slot();
+        }
+        for i in 0..RANKED_BIDS {
+            let current = ranked[i];
+            if ranks_above(carry, current) {
+                ranked[i] = carry;
+                carry = current;
             }
+        }
+
+        AuctionState {
+            ranked,
+            reserve: state.reserve,
+            defaulters: record_defaulter(state.defaulters, bid.bidder, bid.amount > max_collateral),
+        }
+    }
+
+    /// Produces the encrypted empty state every auction starts from
+    #[instruction]
+    pub fn init_auction_state(
+        reserve_ctxt: Enc<Shared, u64>,
+        mxe: Mxe,
+        min_price: u64,
+        hidden_reserve: bool,
+    ) -> Enc<Mxe, AuctionState> {
+        let hidden = reserve_ctxt.to_arcis();
+        mxe.from_arcis(empty_state(hidden, min_price, hidden_reserve))
+    }
+
+    /// The running state is read from the AuctionBidState account, never from the client.
+    /// The bidder identity (flattened pubkey), collateral and lock slot are plaintext:
+    /// all come from the bidder's on-chain BidEscrow, not from the client.
+    /// Equal bids rank by the earlier lock slot, then the lower bidder pubkey,
+    /// whatever order the cluster runs them in.
+    #[instruction]
+    pub fn submit_bid(
+        input_ctxt: Enc<Shared, BidInputs>,
+        state_ctxt: Enc<Mxe, AuctionState>,
+        new_bidder_0: u64,
+        new_bidder_1: u64,
+        new_bidder_2: u64,
+        new_bidder_3: u64,
+        max_collateral: u64,
+        locked_slot: u64,
+    ) -> Enc<Mxe, AuctionState> {
+        let input = input_ctxt.to_arcis();
+        let state = state_ctxt.to_arcis();
+        let bid = BidSlot {
+            amount: input.new_bid,
+            bidder: Bidder {
+                w0: new_bidder_0,
+                w1: new_bidder_1,
+                w2: new_bidder_2,
+                w3: new_bidder_3,
+            },
+            locked_slot,
         };
-        
-        input_ctxt.owner.from_arcis(output)
+
+        state_ctxt.owner.from_arcis(apply_bid(state, bid, max_collateral))
+    }
+
+    /// Removes the bid of a BidEscrow withdrawn before the auction ended.
+    /// Every bid below it moves up, so a retracted leader is replaced by the
+    /// runner-up and the runner-up by the next bid. A later escrow of the same
+    /// bidder has a later lock slot and keeps its bid.
+    #[instruction]
+    pub fn retract_bid(
+        state_ctxt: Enc<Mxe, AuctionState>,
+        bidder_0: u64,
+        bidder_1: u64,
+        bidder_2: u64,
+        bidder_3: u64,
+        locked_slot: u64,
+    ) -> Enc<Mxe, AuctionState> {
+        let state = state_ctxt.to_arcis();
+        let escrow = BidSlot {
+            amount: 0,
+            bidder: Bidder {
+                w0: bidder_0,
+                w1: bidder_1,
+                w2: bidder_2,
+                w3: bidder_3,
+            },
+            locked_slot,
+        };
+
+        state_ctxt.owner.from_arcis(AuctionState {
+            ranked: remove_escrow(state.ranked, escrow, true),
+            reserve: state.reserve,
+            defaulters: state.defaulters,
+        })
+    }
+
+    /// second_bid is only revealed when it is the price: for second-price
+    /// auctions with a winner it is the runner-up's bid floored at the reserve,
+    /// so the reserve itself stays hidden unless it is the price. Otherwise it
+    /// is 0 and the runner-up's bid stays sealed.
+    #[instruction]
+    pub fn reveal_winner(state_ctxt: Enc<Mxe, AuctionState>, second_price: bool) -> AuctionOutcome {
+        let state = state_ctxt.to_arcis();
+        let top = state.ranked[0];
+        let runner_up = state.ranked[1];
+        let prices_by_second = second_price && top.amount > 0;
+        let floored = if runner_up.amount < state.reserve { state.reserve } else { runner_up.amount };
+        AuctionOutcome {
+            max_bid: top.amount,
+            second_bid: if prices_by_second { floored } else { 0 },
+            winner_0: top.bidder.w0,
+            winner_1: top.bidder.w1,
+            winner_2: top.bidder.w2,
+            winner_3: top.bidder.w3,
+            defaulters: state.defaulters,
+        }
+        .reveal()
+    }
+
+    /// Multi-unit counterpart of init_auction_state
+    #[instruction]
+    pub fn init_multi_unit_state(
+        reserve_ctxt: Enc<Shared, u64>,
+        mxe: Mxe,
+        min_price: u64,
+        hidden_reserve: bool,
+    ) -> Enc<Mxe, AuctionState> {
+        let hidden = reserve_ctxt.to_arcis();
+        mxe.from_arcis(empty_state(hidden, min_price, hidden_reserve))
     }
 
+    /// Same ranking as submit_bid, for the MultiUnitBidState
     #[instruction]
-    pub fn reveal_winner(input_ctxt: Enc<Shared, AuctionState>) -> AuctionState {
+    pub fn submit_multi_unit_bid(
+        input_ctxt: Enc<Shared, BidInputs>,
+        state_ctxt: Enc<Mxe, AuctionState>,
+        new_bidder_0: u64,
+        new_bidder_1: u64,
+        new_bidder_2: u64,
+        new_bidder_3: u64,
+        max_collateral: u64,
+        locked_slot: u64,
+    ) -> Enc<Mxe, AuctionState> {
         let input = input_ctxt.to_arcis();
-        input.reveal()
+        let state = state_ctxt.to_arcis();
+        let bid = BidSlot {
+            amount: input.new_bid,
+            bidder: Bidder {
+                w0: new_bidder_0,
+                w1: new_bidder_1,
+                w2: new_bidder_2,
+                w3: new_bidder_3,
+            },
+            locked_slot,
+        };
+
+        state_ctxt.owner.from_arcis(apply_bid(state, bid, max_collateral))
+    }
+
+    /// The first `units` filled slots win. First-price auctions clear at the
+    /// lowest winning bid; second-price auctions at the highest losing bid,
+    /// floored at the reserve. Nothing is priced without a winner.
+    #[instruction]
+    pub fn reveal_multi_unit(
+        state_ctxt: Enc<Mxe, AuctionState>,
+        units: u64,
+        second_price: bool,
+    ) -> MultiUnitOutcome {
+        let state = state_ctxt.to_arcis();
+
+        let mut winners = [Bidder { w0: 0, w1: 0, w2: 0, w3: 0 }; MAX_UNITS];
+        let mut winner_count = 0;
+        let mut lowest_winning_bid = 0;
+        for i in 0..MAX_UNITS {
+            let wins = (i as u64) < units && state.ranked[i].amount > 0;
+            if wins {
+                winners[i] = state.ranked[i].bidder;
+                winner_count += 1;
+                lowest_winning_bid = state.ranked[i].amount;
+            }
+        }
+
+        let mut highest_losing_bid = 0;
+        for i in 0..RANKED_BIDS {
+            if (i as u64) == units {
+                highest_losing_bid = state.ranked[i].amount;
+            }
+        }
+
+        let price = if second_price {
+            if highest_losing_bid > state.reserve { highest_losing_bid } else { state.reserve }
+        } else {
+            lowest_winning_bid
+        };
+
+        MultiUnitOutcome {
+            clearing_price: if winner_count > 0 { price } else { 0 },
+            winner_count,
+            winners,
+            defaulters: state.defaulters,
+        }
+        .reveal()
     }
 }
@@ -3,17 +3,57 @@ use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::{CallbackAccount, CircuitSource, OffChainCircuitSource};
 use arcium_macros::circuit_hash;
 
+const COMP_DEF_OFFSET_INIT_AUCTION_STATE: u32 = comp_def_offset("init_auction_state");
 const COMP_DEF_OFFSET_SUBMIT_BID: u32 = comp_def_offset("submit_bid");
 const COMP_DEF_OFFSET_REVEAL_WINNER: u32 = comp_def_offset("reveal_winner");
+const COMP_DEF_OFFSET_RETRACT_BID: u32 = comp_def_offset("retract_bid");
+const COMP_DEF_OFFSET_INIT_MULTI_UNIT_STATE: u32 = comp_def_offset("init_multi_unit_state");
+const COMP_DEF_OFFSET_SUBMIT_MULTI_UNIT_BID: u32 = comp_def_offset("submit_multi_unit_bid");
+const COMP_DEF_OFFSET_REVEAL_MULTI_UNIT: u32 = comp_def_offset("reveal_multi_unit");
 
 // Circuit URLs - points to compiled .arcis files in the repo
+const INIT_AUCTION_STATE_CIRCUIT_URL: &str = "https://raw.githubusercontent.com/nikhilxhub/Sealed/main/arcium_program/build/init_auction_state.arcis";
 const SUBMIT_BID_CIRCUIT_URL: &str = "https://raw.githubusercontent.com/nikhilxhub/Sealed/main/arcium_program/build/submit_bid.arcis";
 const REVEAL_WINNER_CIRCUIT_URL: &str = "https://raw.githubusercontent.com/nikhilxhub/Sealed/main/arcium_program/build/reveal_winner.arcis";
+const RETRACT_BID_CIRCUIT_URL: &str = "https://raw.githubusercontent.com/nikhilxhub/Sealed/main/arcium_program/build/retract_bid.arcis";
+const INIT_MULTI_UNIT_STATE_CIRCUIT_URL: &str = "https://raw.githubusercontent.com/nikhilxhub/Sealed/main/arcium_program/build/init_multi_unit_state.arcis";
+const SUBMIT_MULTI_UNIT_BID_CIRCUIT_URL: &str = "https://raw.githubusercontent.com/nikhilxhub/Sealed/main/arcium_program/build/submit_multi_unit_bid.arcis";
+const REVEAL_MULTI_UNIT_CIRCUIT_URL: &str = "https://raw.githubusercontent.com/nikhilxhub/Sealed/main/arcium_program/build/reveal_multi_unit.arcis";
 
 // Seeds for PDAs
 const AUCTION_STATE_SEED: &[u8] = b"auction_bid_state";
+const MULTI_UNIT_STATE_SEED: &[u8] = b"multi_unit_bid_state";
 const AUCTION_RESULT_SEED: &[u8] = b"auction_result";
 
+/// The sealed_auction program ID that owns Auction and BidEscrow accounts
+const SEALED_AUCTION_PROGRAM_ID: Pubkey = pubkey!("2rTWXsHTnJdSKxJjdG1wDWdQYFFD3b6RfHbqi3VsR2dt");
+
+/// Seed for bid escrow PDA (must match sealed_auction)
+const BID_ESCROW_SEED: &[u8] = b"bid_escrow";
+
+/// Seed for bid withdrawal receipt PDA (must match sealed_auction)
+const BID_WITHDRAWAL_SEED: &[u8] = b"bid_withdrawal";
+
+/// Byte offset of the encrypted AuctionState within AuctionBidState
+/// (discriminator + auction_id + bump + bid_count)
+const AUCTION_STATE_CIPHERTEXT_OFFSET: u32 = 8 + 32 + 1 + 8;
+/// Over-collateral bidders tracked per auction (must match sealed_auction and the circuits)
+const MAX_DEFAULTERS: usize = 4;
+/// Number of ciphertexts in the encrypted Defaulters list: count, then bidder_0..3 each
+const DEFAULTERS_CIPHERTEXT_COUNT: usize = 1 + MAX_DEFAULTERS * 4;
+
+/// Units a multi-unit auction can sell (must match sealed_auction and the circuits)
+const MAX_UNITS: usize = 4;
+/// Bids ranked in the encrypted AuctionState (must match sealed_auction and the circuits)
+const RANKED_BIDS: usize = 8;
+/// Number of ciphertexts in the encrypted AuctionState, for single- and multi-unit auctions:
+/// RANKED_BIDS slots of (amount, bidder_0..3, locked_slot), the reserve, then the defaulters
+const AUCTION_STATE_CIPHERTEXT_COUNT: usize = RANKED_BIDS * 6 + 1 + DEFAULTERS_CIPHERTEXT_COUNT;
+
+/// Slots after which a pending computation whose callback never arrived
+/// may be released by anyone (about ten minutes)
+const PENDING_COMPUTATION_TIMEOUT_SLOTS: u64 = 1_500;
+
 declare_id!("2excUVgCNGZDN4yHGBbxBg4ptNYTH1nyqnJ5HArAG6wC");
 
 /// Helper: Reconstruct a Pubkey from 4 u64 chunks (little-endian)
@@ -26,10 +66,50 @@ fn reconstruct_pubkey(chunk0: u64, chunk1: u64, chunk2: u64, chunk3: u64) -> Pub
     Pubkey::new_from_array(bytes)
 }
 
+/// Helper: Split a Pubkey into 4 u64 chunks (little-endian), inverse of reconstruct_pubkey
+fn split_pubkey(key: &Pubkey) -> [u64; 4] {
+    let bytes = key.to_bytes();
+    let mut chunks = [0u64; 4];
+    for (i, chunk) in chunks.iter_mut().enumerate() {
+        *chunk = u64::from_le_bytes(bytes[i * 8..(i + 1) * 8].try_into().unwrap());
+    }
+    chunks
+}
+
+/// Helper: Clear a pending computation lock once it has timed out
+fn release_stale_lock(pending_computation: &mut Option<Pubkey>, pending_since_slot: u64) -> Result<()> {
+    require!(pending_computation.is_some(), ErrorCode::NoPendingComputation);
+    require!(
+        Clock::get()?.slot > pending_since_slot.saturating_add(PENDING_COMPUTATION_TIMEOUT_SLOTS),
+        ErrorCode::ComputationNotStale
+    );
+    *pending_computation = None;
+    Ok(())
+}
+
+/// Helper: Load a sealed_auction account after verifying it is owned by that program
+fn load_sealed_auction_account<T: AccountDeserialize>(info: &AccountInfo) -> Result<T> {
+    require_keys_eq!(*info.owner, SEALED_AUCTION_PROGRAM_ID, ErrorCode::InvalidSealedAuctionAccount);
+    T::try_deserialize(&mut &info.data.borrow()[..])
+}
+
 #[arcium_program]
 pub mod arcium_program {
     use super::*;
 
+    /// Initialize computation definition for init_auction_state circuit
+    pub fn init_init_auction_state_comp_def(ctx: Context<InitInitAuctionStateCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: INIT_AUCTION_STATE_CIRCUIT_URL.to_string(),
+                hash: circuit_hash!("init_auction_state"),
+            })),
+            None
+        )?;
+        Ok(())
+    }
+
     /// Initialize computation definition for submit_bid circuit
     pub fn init_submit_bid_comp_def(ctx: Context<InitSubmitBidCompDef>) -> Result<()> {
         init_comp_def(
@@ -56,59 +136,188 @@ pub mod arcium_program {
         Ok(())
     }
 
+    /// Initialize computation definition for retract_bid circuit
+    pub fn init_retract_bid_comp_def(ctx: Context<InitRetractBidCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: RETRACT_BID_CIRCUIT_URL.to_string(),
+                hash: circuit_hash!("retract_bid"),
+            })),
+            None
+        )?;
+        Ok(())
+    }
+
+    /// Initialize computation definition for init_multi_unit_state circuit
+    pub fn init_init_multi_unit_state_comp_def(ctx: Context<InitInitMultiUnitStateCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: INIT_MULTI_UNIT_STATE_CIRCUIT_URL.to_string(),
+                hash: circuit_hash!("init_multi_unit_state"),
+            })),
+            None
+        )?;
+        Ok(())
+    }
+
+    /// Initialize computation definition for submit_multi_unit_bid circuit
+    pub fn init_submit_multi_unit_bid_comp_def(ctx: Context<InitSubmitMultiUnitBidCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: SUBMIT_MULTI_UNIT_BID_CIRCUIT_URL.to_string(),
+                hash: circuit_hash!("submit_multi_unit_bid"),
+            })),
+            None
+        )?;
+        Ok(())
+    }
+
+    /// Initialize computation definition for reveal_multi_unit circuit
+    pub fn init_reveal_multi_unit_comp_def(ctx: Context<InitRevealMultiUnitCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: REVEAL_MULTI_UNIT_CIRCUIT_URL.to_string(),
+                hash: circuit_hash!("reveal_multi_unit"),
+            })),
+            None
+        )?;
+        Ok(())
+    }
+
     /// Initialize auction bid state account for a specific auction
-    /// This must be called once before any bids are submitted for an auction
+    /// This must be called once, by the seller, before any bids are submitted for an auction.
+    /// Queues the init_auction_state computation, which writes the encrypted empty state.
+    /// For hidden-reserve auctions `encrypted_reserve` is the seller's reserve encrypted
+    /// with their shared key; otherwise it is ignored and the public min_price is used.
     pub fn initialize_auction_state(
         ctx: Context<InitializeAuctionState>,
-        _auction_id: Pubkey,
+        computation_offset: u64,
+        auction_id: Pubkey,
+        encryption_pubkey: [u8; 32],
+        reserve_nonce: u128,
+        encrypted_reserve: [u8; 32],
+        nonce: u128,
     ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Only the seller may set the reserve bids are compared against
+        let auction: Auction = load_sealed_auction_account(&ctx.accounts.auction)?;
+        require_keys_eq!(auction.seller, ctx.accounts.payer.key(), ErrorCode::NotSeller);
+        // Multi-unit auctions keep their bids in a MultiUnitBidState instead
+        require!(auction.units == 1, ErrorCode::UnitsMismatch);
+
         let state = &mut ctx.accounts.auction_bid_state;
-        state.auction_id = _auction_id;
+        state.auction_id = auction_id;
         state.bump = ctx.bumps.auction_bid_state;
         state.bid_count = 0;
-        state.encrypted_max_bid = [0u8; 32];
-        state.encrypted_winner_0 = [0u8; 32];
-        state.encrypted_winner_1 = [0u8; 32];
-        state.encrypted_winner_2 = [0u8; 32];
-        state.encrypted_winner_3 = [0u8; 32];
+        state.encrypted_state = [[0u8; 32]; AUCTION_STATE_CIPHERTEXT_COUNT];
         state.nonce = 0;
+        state.initialized = false;
+        state.pending_computation = None;
+        state.pending_since_slot = 0;
+        state.second_price = auction.auction_type == AuctionType::SecondPrice;
+        state.retracted_count = 0;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(encryption_pubkey)
+            .plaintext_u128(reserve_nonce)
+            .encrypted_u64(encrypted_reserve)
+            .plaintext_u128(nonce)
+            .plaintext_u64(auction.min_price)
+            .plaintext_bool(auction.hidden_reserve)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitAuctionStateCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.auction_bid_state.key(),
+                        is_writable: true,
+                    },
+                ]
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
+    /// Callback from init computation - stores the encrypted empty state
+    #[arcium_callback(encrypted_ix = "init_auction_state")]
+    pub fn init_auction_state_callback(
+        ctx: Context<InitAuctionStateCallback>,
+        output: SignedComputationOutputs<InitAuctionStateOutput>,
+    ) -> Result<()> {
+        let o = match output.verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account) {
+            Ok(InitAuctionStateOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let state = &mut ctx.accounts.auction_bid_state;
+        state.store_encrypted_state(&o.ciphertexts, o.nonce);
+        state.initialized = true;
         Ok(())
     }
 
     /// Submit a bid with encrypted values
+    /// The running auction state is read by the MPC cluster straight from the
+    /// AuctionBidState account; the bidder only supplies their own encrypted bid.
+    /// The payer must be the bidder of a funded sealed_auction BidEscrow for this auction;
+    /// the bidder identity and locked collateral are taken from that escrow.
     pub fn submit_bid(
         ctx: Context<SubmitBid>,
         computation_offset: u64,
         encryption_pubkey: [u8; 32],
         nonce: u128,
-        current_max_bid: [u8; 32],
-        current_winner_0: [u8; 32],
-        current_winner_1: [u8; 32],
-        current_winner_2: [u8; 32],
-        current_winner_3: [u8; 32],
         new_bid_amount: [u8; 32],
-        new_bidder_0: [u8; 32],
-        new_bidder_1: [u8; 32],
-        new_bidder_2: [u8; 32],
-        new_bidder_3: [u8; 32],
-        min_price: [u8; 32],
     ) -> Result<()> {
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
+        // Bind the bid to the bidder's on-chain collateral
+        let escrow: BidEscrow = load_sealed_auction_account(&ctx.accounts.bid_escrow)?;
+        require_keys_eq!(escrow.bidder, ctx.accounts.payer.key(), ErrorCode::BidderMismatch);
+        require_keys_eq!(escrow.auction, ctx.accounts.auction_bid_state.auction_id, ErrorCode::AuctionMismatch);
+        require!(!escrow.withdrawn, ErrorCode::EscrowWithdrawn);
+
+        // Only bids inside the auction's bidding window are accepted
+        let auction: Auction = load_sealed_auction_account(&ctx.accounts.auction)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= auction.start_time, ErrorCode::AuctionNotStarted);
+        require!(now < auction.end_time, ErrorCode::AuctionEnded);
+
+        // Bids are applied one at a time so no update to the running state is lost.
+        // Pending retractions don't block them: retract_bid only removes the slot
+        // of the withdrawn escrow, identified by its lock slot.
+        let state = &mut ctx.accounts.auction_bid_state;
+        require!(state.initialized, ErrorCode::AuctionStateNotInitialized);
+        require!(state.pending_computation.is_none(), ErrorCode::ComputationPending);
+        state.pending_computation = Some(ctx.accounts.computation_account.key());
+        state.pending_since_slot = Clock::get()?.slot;
+
+        let bidder = split_pubkey(&escrow.bidder);
+
         let args = ArgBuilder::new()
             .x25519_pubkey(encryption_pubkey)
             .plaintext_u128(nonce)
-            .encrypted_u64(current_max_bid)
-            .encrypted_u64(current_winner_0)
-            .encrypted_u64(current_winner_1)
-            .encrypted_u64(current_winner_2)
-            .encrypted_u64(current_winner_3)
             .encrypted_u64(new_bid_amount)
-            .encrypted_u64(new_bidder_0)
-            .encrypted_u64(new_bidder_1)
-            .encrypted_u64(new_bidder_2)
-            .encrypted_u64(new_bidder_3)
-            .encrypted_u64(min_price)
+            .plaintext_u128(state.nonce)
+            .account(state.key(), AUCTION_STATE_CIPHERTEXT_OFFSET, 32 * AUCTION_STATE_CIPHERTEXT_COUNT as u32)
+            .plaintext_u64(bidder[0])
+            .plaintext_u64(bidder[1])
+            .plaintext_u64(bidder[2])
+            .plaintext_u64(bidder[3])
+            .plaintext_u64(escrow.max_locked_amount)
+            .plaintext_u64(escrow.locked_slot)
             .build();
 
         queue_computation(
@@ -133,28 +342,36 @@ pub mod arcium_program {
     }
 
     /// Callback from MPC computation - updates auction state with new encrypted values
+    /// An aborted computation releases the pending slot and leaves the state untouched,
+    /// so later bids are not blocked.
     #[arcium_callback(encrypted_ix = "submit_bid")]
     pub fn submit_bid_callback(
         ctx: Context<SubmitBidCallback>,
         output: SignedComputationOutputs<SubmitBidOutput>,
     ) -> Result<()> {
+        let state = &mut ctx.accounts.auction_bid_state;
+        require!(
+            state.pending_computation == Some(ctx.accounts.computation_account.key()),
+            ErrorCode::UnexpectedComputation
+        );
+        state.pending_computation = None;
+
         let o = match output.verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account) {
             Ok(SubmitBidOutput { field_0 }) => field_0,
-            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+            Err(_) => {
+                msg!("submit_bid computation aborted, bid discarded");
+                return Ok(());
+            }
         };
 
-        let state = &mut ctx.accounts.auction_bid_state;
-        state.encrypted_max_bid = o.ciphertexts[0];
-        state.encrypted_winner_0 = o.ciphertexts[1];
-        state.encrypted_winner_1 = o.ciphertexts[2];
-        state.encrypted_winner_2 = o.ciphertexts[3];
-        state.encrypted_winner_3 = o.ciphertexts[4];
-        state.nonce = o.nonce;
+        state.store_encrypted_state(&o.ciphertexts, o.nonce);
         state.bid_count += 1;
 
+        // The top slot is (amount, bidder_0..3, locked_slot); the runner-up's amount follows
         emit!(AuctionUpdatedEvent {
             auction_id: state.auction_id,
             new_max_bid: o.ciphertexts[0],
+            new_second_bid: o.ciphertexts[6],
             new_winner_0: o.ciphertexts[1],
             new_winner_1: o.ciphertexts[2],
             new_winner_2: o.ciphertexts[3],
@@ -165,16 +382,119 @@ pub mod arcium_program {
         Ok(())
     }
 
+    /// Drop a bid withdrawn through sealed_auction::withdraw_bid from the running state.
+    /// Permissionless: anyone can crank the next unprocessed BidWithdrawal receipt,
+    /// during bidding or after the end; reveal_winner waits for all of them.
+    pub fn retract_bid(
+        ctx: Context<RetractBid>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let receipt: BidWithdrawal = load_sealed_auction_account(&ctx.accounts.bid_withdrawal)?;
+        require_keys_eq!(receipt.auction, ctx.accounts.auction_bid_state.auction_id, ErrorCode::AuctionMismatch);
+
+        let state = &mut ctx.accounts.auction_bid_state;
+        require!(state.initialized, ErrorCode::AuctionStateNotInitialized);
+        require!(state.pending_computation.is_none(), ErrorCode::ComputationPending);
+        require!(receipt.index == state.retracted_count, ErrorCode::UnexpectedWithdrawal);
+        state.pending_computation = Some(ctx.accounts.computation_account.key());
+        state.pending_since_slot = Clock::get()?.slot;
+
+        let bidder = split_pubkey(&receipt.bidder);
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(state.nonce)
+            .account(state.key(), AUCTION_STATE_CIPHERTEXT_OFFSET, 32 * AUCTION_STATE_CIPHERTEXT_COUNT as u32)
+            .plaintext_u64(bidder[0])
+            .plaintext_u64(bidder[1])
+            .plaintext_u64(bidder[2])
+            .plaintext_u64(bidder[3])
+            .plaintext_u64(receipt.locked_slot)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RetractBidCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.auction_bid_state.key(),
+                        is_writable: true,
+                    },
+                ]
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
+    /// Callback from retract computation. An aborted computation leaves the
+    /// receipt unprocessed, so retract_bid can simply be called again.
+    #[arcium_callback(encrypted_ix = "retract_bid")]
+    pub fn retract_bid_callback(
+        ctx: Context<RetractBidCallback>,
+        output: SignedComputationOutputs<RetractBidOutput>,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.auction_bid_state;
+        require!(
+            state.pending_computation == Some(ctx.accounts.computation_account.key()),
+            ErrorCode::UnexpectedComputation
+        );
+        state.pending_computation = None;
+
+        let o = match output.verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account) {
+            Ok(RetractBidOutput { field_0 }) => field_0,
+            Err(_) => {
+                msg!("retract_bid computation aborted, withdrawal not yet processed");
+                return Ok(());
+            }
+        };
+
+        state.store_encrypted_state(&o.ciphertexts, o.nonce);
+        state.retracted_count += 1;
+
+        emit!(BidRetractedEvent {
+            auction_id: state.auction_id,
+            retracted_count: state.retracted_count,
+        });
+        Ok(())
+    }
+
+    /// Release the pending computation lock when its callback never arrived,
+    /// e.g. because the cluster dropped the computation. Permissionless, after
+    /// PENDING_COMPUTATION_TIMEOUT_SLOTS. A callback landing afterwards no longer
+    /// matches pending_computation and is rejected, so the state is untouched.
+    pub fn release_stale_computation(ctx: Context<ReleaseStaleComputation>) -> Result<()> {
+        let state = &mut ctx.accounts.auction_bid_state;
+        release_stale_lock(&mut state.pending_computation, state.pending_since_slot)
+    }
+
     /// Reveal the winner - decrypts the final auction state
     /// Call this after auction ends to get plaintext winner info and enable settlement
     pub fn reveal_winner(
         ctx: Context<RevealWinner>,
         computation_offset: u64,
-        encryption_pubkey: [u8; 32],
-        nonce: u128,
     ) -> Result<()> {
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
+        // Revealing before the auction ends would leak the current leader to other bidders
+        let auction: Auction = load_sealed_auction_account(&ctx.accounts.auction)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now > auction.end_time, ErrorCode::AuctionNotEnded);
+
+        // Never reveal a state that a queued bid is about to change,
+        // or one that still contains a withdrawn bidder
+        let state = &ctx.accounts.auction_bid_state;
+        require!(state.initialized, ErrorCode::AuctionStateNotInitialized);
+        require!(state.pending_computation.is_none(), ErrorCode::ComputationPending);
+        require!(state.retracted_count == auction.withdrawal_count, ErrorCode::RetractionPending);
+
         // Initialize auction_result account
         let result = &mut ctx.accounts.auction_result;
         result.auction_id = ctx.accounts.auction_bid_state.auction_id;
@@ -183,17 +503,68 @@ pub mod arcium_program {
         result.winner = Pubkey::default();
         result.winning_amount = 0;
         result.revealed_at = 0;
+        result.second_amount = 0;
+        result.attempts = 1;
+        result.last_computation_offset = computation_offset;
+        result.winner_count = 0;
+        result.winners = [Pubkey::default(); MAX_UNITS];
+        result.defaulter_count = 0;
+        result.defaulters = [Pubkey::default(); MAX_DEFAULTERS];
 
         let state = &ctx.accounts.auction_bid_state;
 
         let args = ArgBuilder::new()
-            .x25519_pubkey(encryption_pubkey)
-            .plaintext_u128(nonce)
-            .encrypted_u64(state.encrypted_max_bid)
-            .encrypted_u64(state.encrypted_winner_0)
-            .encrypted_u64(state.encrypted_winner_1)
-            .encrypted_u64(state.encrypted_winner_2)
-            .encrypted_u64(state.encrypted_winner_3)
+            .plaintext_u128(state.nonce)
+            .account(state.key(), AUCTION_STATE_CIPHERTEXT_OFFSET, 32 * AUCTION_STATE_CIPHERTEXT_COUNT as u32)
+            .plaintext_bool(state.second_price)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealWinnerCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.auction_bid_state.key(),
+                        is_writable: false,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.auction_result.key(),
+                        is_writable: true,
+                    },
+                ]
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
+    /// Re-queue the reveal for an AuctionResult whose computation was aborted
+    /// or whose callback failed. Permissionless, like reveal_winner.
+    pub fn retry_reveal(
+        ctx: Context<RetryReveal>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let state = &ctx.accounts.auction_bid_state;
+        require!(state.pending_computation.is_none(), ErrorCode::ComputationPending);
+
+        let result = &mut ctx.accounts.auction_result;
+        result.attempts = result.attempts.saturating_add(1);
+        result.last_computation_offset = computation_offset;
+
+        let state = &ctx.accounts.auction_bid_state;
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(state.nonce)
+            .account(state.key(), AUCTION_STATE_CIPHERTEXT_OFFSET, 32 * AUCTION_STATE_CIPHERTEXT_COUNT as u32)
+            .plaintext_bool(state.second_price)
             .build();
 
         queue_computation(
@@ -233,90 +604,1169 @@ pub mod arcium_program {
         };
 
         // Reconstruct winner pubkey from u64 chunks
-        let winner = reconstruct_pubkey(o.field_1, o.field_2, o.field_3, o.field_4);
+        let winner = reconstruct_pubkey(o.field_2, o.field_3, o.field_4, o.field_5);
+
+        // An earlier attempt may have landed after a retry was queued
+        let result = &mut ctx.accounts.auction_result;
+        require!(!result.revealed, ErrorCode::AlreadyRevealed);
+
+        // Store plaintext result for settlement
+        result.winner = winner;
+        result.winning_amount = o.field_0;
+        result.second_amount = o.field_1;
+        result.revealed_at = Clock::get()?.unix_timestamp;
+        result.revealed = true;
+        if winner != Pubkey::default() {
+            result.winner_count = 1;
+            result.winners[0] = winner;
+        }
+        result.store_defaulters(
+            o.field_6.field_0,
+            o.field_6.field_1.iter().map(|b| [b.field_0, b.field_1, b.field_2, b.field_3]),
+        );
+
+        // Emit event for indexers
+        emit!(AuctionResultEvent {
+            auction_id: ctx.accounts.auction_bid_state.auction_id,
+            winning_bid: o.field_0,
+            second_bid: o.field_1,
+            winner,
+            defaulters: result.defaulters[..result.defaulter_count as usize].to_vec(),
+        });
+
+        Ok(())
+    }
+
+    /// Multi-unit counterpart of initialize_auction_state, for auctions
+    /// listed with a Lot of more than one unit
+    pub fn initialize_multi_unit_state(
+        ctx: Context<InitializeMultiUnitState>,
+        computation_offset: u64,
+        auction_id: Pubkey,
+        encryption_pubkey: [u8; 32],
+        reserve_nonce: u128,
+        encrypted_reserve: [u8; 32],
+        nonce: u128,
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let auction: Auction = load_sealed_auction_account(&ctx.accounts.auction)?;
+        require_keys_eq!(auction.seller, ctx.accounts.payer.key(), ErrorCode::NotSeller);
+        require!(auction.units > 1, ErrorCode::UnitsMismatch);
+
+        let state = &mut ctx.accounts.multi_unit_bid_state;
+        state.auction_id = auction_id;
+        state.bump = ctx.bumps.multi_unit_bid_state;
+        state.bid_count = 0;
+        state.encrypted_state = [[0u8; 32]; AUCTION_STATE_CIPHERTEXT_COUNT];
+        state.nonce = 0;
+        state.initialized = false;
+        state.pending_computation = None;
+        state.pending_since_slot = 0;
+        state.second_price = auction.auction_type == AuctionType::SecondPrice;
+        state.units = auction.units;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(encryption_pubkey)
+            .plaintext_u128(reserve_nonce)
+            .encrypted_u64(encrypted_reserve)
+            .plaintext_u128(nonce)
+            .plaintext_u64(auction.min_price)
+            .plaintext_bool(auction.hidden_reserve)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitMultiUnitStateCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.multi_unit_bid_state.key(),
+                        is_writable: true,
+                    },
+                ]
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
+    /// Callback from init computation - stores the encrypted empty top-N state
+    #[arcium_callback(encrypted_ix = "init_multi_unit_state")]
+    pub fn init_multi_unit_state_callback(
+        ctx: Context<InitMultiUnitStateCallback>,
+        output: SignedComputationOutputs<InitMultiUnitStateOutput>,
+    ) -> Result<()> {
+        let o = match output.verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account) {
+            Ok(InitMultiUnitStateOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let state = &mut ctx.accounts.multi_unit_bid_state;
+        state.store_encrypted_state(&o.ciphertexts, o.nonce);
+        state.initialized = true;
+        Ok(())
+    }
+
+    /// Submit a bid for one unit of a multi-unit auction.
+    /// Same escrow binding and bidding window as submit_bid.
+    pub fn submit_multi_unit_bid(
+        ctx: Context<SubmitMultiUnitBid>,
+        computation_offset: u64,
+        encryption_pubkey: [u8; 32],
+        nonce: u128,
+        new_bid_amount: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let escrow: BidEscrow = load_sealed_auction_account(&ctx.accounts.bid_escrow)?;
+        require_keys_eq!(escrow.bidder, ctx.accounts.payer.key(), ErrorCode::BidderMismatch);
+        require_keys_eq!(escrow.auction, ctx.accounts.multi_unit_bid_state.auction_id, ErrorCode::AuctionMismatch);
+        require!(!escrow.withdrawn, ErrorCode::EscrowWithdrawn);
+
+        let auction: Auction = load_sealed_auction_account(&ctx.accounts.auction)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= auction.start_time, ErrorCode::AuctionNotStarted);
+        require!(now < auction.end_time, ErrorCode::AuctionEnded);
+
+        // Multi-unit bids can't be withdrawn, so nothing is ever retracted from this state
+        let state = &mut ctx.accounts.multi_unit_bid_state;
+        require!(state.initialized, ErrorCode::AuctionStateNotInitialized);
+        require!(state.pending_computation.is_none(), ErrorCode::ComputationPending);
+        state.pending_computation = Some(ctx.accounts.computation_account.key());
+        state.pending_since_slot = Clock::get()?.slot;
+
+        let bidder = split_pubkey(&escrow.bidder);
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(encryption_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(new_bid_amount)
+            .plaintext_u128(state.nonce)
+            .account(
+                state.key(),
+                AUCTION_STATE_CIPHERTEXT_OFFSET,
+                32 * AUCTION_STATE_CIPHERTEXT_COUNT as u32,
+            )
+            .plaintext_u64(bidder[0])
+            .plaintext_u64(bidder[1])
+            .plaintext_u64(bidder[2])
+            .plaintext_u64(bidder[3])
+            .plaintext_u64(escrow.max_locked_amount)
+            .plaintext_u64(escrow.locked_slot)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SubmitMultiUnitBidCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.multi_unit_bid_state.key(),
+                        is_writable: true,
+                    },
+                ]
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
+    /// Callback from multi-unit bid computation. Like submit_bid_callback,
+    /// an aborted computation only releases the pending slot.
+    #[arcium_callback(encrypted_ix = "submit_multi_unit_bid")]
+    pub fn submit_multi_unit_bid_callback(
+        ctx: Context<SubmitMultiUnitBidCallback>,
+        output: SignedComputationOutputs<SubmitMultiUnitBidOutput>,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.multi_unit_bid_state;
+        require!(
+            state.pending_computation == Some(ctx.accounts.computation_account.key()),
+            ErrorCode::UnexpectedComputation
+        );
+        state.pending_computation = None;
+
+        let o = match output.verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account) {
+            Ok(SubmitMultiUnitBidOutput { field_0 }) => field_0,
+            Err(_) => {
+                msg!("submit_multi_unit_bid computation aborted, bid discarded");
+                return Ok(());
+            }
+        };
+
+        state.store_encrypted_state(&o.ciphertexts, o.nonce);
+        state.bid_count += 1;
+
+        emit!(MultiUnitUpdatedEvent {
+            auction_id: state.auction_id,
+            nonce: o.nonce,
+            bid_count: state.bid_count,
+        });
+        Ok(())
+    }
+
+    /// Multi-unit counterpart of release_stale_computation
+    pub fn release_stale_multi_unit_computation(ctx: Context<ReleaseStaleMultiUnitComputation>) -> Result<()> {
+        let state = &mut ctx.accounts.multi_unit_bid_state;
+        release_stale_lock(&mut state.pending_computation, state.pending_since_slot)
+    }
+
+    /// Reveal the winners and uniform clearing price of a multi-unit auction
+    /// into the same AuctionResult PDA single-unit auctions use
+    pub fn reveal_multi_unit(
+        ctx: Context<RevealMultiUnit>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let auction: Auction = load_sealed_auction_account(&ctx.accounts.auction)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now > auction.end_time, ErrorCode::AuctionNotEnded);
+
+        let state = &ctx.accounts.multi_unit_bid_state;
+        require!(state.initialized, ErrorCode::AuctionStateNotInitialized);
+        require!(state.pending_computation.is_none(), ErrorCode::ComputationPending);
+
+        let result = &mut ctx.accounts.auction_result;
+        result.auction_id = ctx.accounts.multi_unit_bid_state.auction_id;
+        result.bump = ctx.bumps.auction_result;
+        result.revealed = false;
+        result.winner = Pubkey::default();
+        result.winning_amount = 0;
+        result.revealed_at = 0;
+        result.second_amount = 0;
+        result.attempts = 1;
+        result.last_computation_offset = computation_offset;
+        result.winner_count = 0;
+        result.winners = [Pubkey::default(); MAX_UNITS];
+        result.defaulter_count = 0;
+        result.defaulters = [Pubkey::default(); MAX_DEFAULTERS];
+
+        let state = &ctx.accounts.multi_unit_bid_state;
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(state.nonce)
+            .account(
+                state.key(),
+                AUCTION_STATE_CIPHERTEXT_OFFSET,
+                32 * AUCTION_STATE_CIPHERTEXT_COUNT as u32,
+            )
+            .plaintext_u64(state.units as u64)
+            .plaintext_bool(state.second_price)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealMultiUnitCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.multi_unit_bid_state.key(),
+                        is_writable: false,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.auction_result.key(),
+                        is_writable: true,
+                    },
+                ]
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
+    /// Multi-unit counterpart of retry_reveal: re-queues reveal_multi_unit for
+    /// an AuctionResult that is still unrevealed. Permissionless.
+    pub fn retry_reveal_multi_unit(
+        ctx: Context<RetryRevealMultiUnit>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let state = &ctx.accounts.multi_unit_bid_state;
+        require!(state.pending_computation.is_none(), ErrorCode::ComputationPending);
+
+        let result = &mut ctx.accounts.auction_result;
+        result.attempts = result.attempts.saturating_add(1);
+        result.last_computation_offset = computation_offset;
+
+        let state = &ctx.accounts.multi_unit_bid_state;
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(state.nonce)
+            .account(
+                state.key(),
+                AUCTION_STATE_CIPHERTEXT_OFFSET,
+                32 * AUCTION_STATE_CIPHERTEXT_COUNT as u32,
+            )
+            .plaintext_u64(state.units as u64)
+            .plaintext_bool(state.second_price)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealMultiUnitCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.multi_unit_bid_state.key(),
+                        is_writable: false,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.auction_result.key(),
+                        is_writable: true,
+                    },
+                ]
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
+    /// Callback from multi-unit reveal. `winner` holds the top bidder so
+    /// finalize_no_winner still recognises an auction nobody won.
+    #[arcium_callback(encrypted_ix = "reveal_multi_unit")]
+    pub fn reveal_multi_unit_callback(
+        ctx: Context<RevealMultiUnitCallback>,
+        output: SignedComputationOutputs<RevealMultiUnitOutput>,
+    ) -> Result<()> {
+        let o = match output.verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account) {
+            Ok(RevealMultiUnitOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let result = &mut ctx.accounts.auction_result;
+        require!(!result.revealed, ErrorCode::AlreadyRevealed);
+
+        let winner_count = (o.field_1 as usize).min(MAX_UNITS);
+        for (i, winner) in o.field_2.iter().take(winner_count).enumerate() {
+            result.winners[i] = reconstruct_pubkey(winner.field_0, winner.field_1, winner.field_2, winner.field_3);
+        }
+        result.winner_count = winner_count as u8;
+        result.winner = result.winners[0];
+        result.winning_amount = o.field_0;
+        result.revealed_at = Clock::get()?.unix_timestamp;
+        result.revealed = true;
+        result.store_defaulters(
+            o.field_3.field_0,
+            o.field_3.field_1.iter().map(|b| [b.field_0, b.field_1, b.field_2, b.field_3]),
+        );
+
+        emit!(MultiUnitResultEvent {
+            auction_id: ctx.accounts.multi_unit_bid_state.auction_id,
+            clearing_price: o.field_0,
+            winners: result.winners[..winner_count].to_vec(),
+            defaulters: result.defaulters[..result.defaulter_count as usize].to_vec(),
+        });
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Account Structs
+// ============================================================================
+
+/// Stores encrypted auction state between bids
+#[account]
+#[derive(InitSpace)]
+pub struct AuctionBidState {
+    pub auction_id: Pubkey,
+    pub bump: u8,
+    pub bid_count: u64,
+    /// Ciphertexts of AuctionState in field order: the ranked bids, the reserve
+    /// (never revealed on its own), then the over-collateral bidders
+    pub encrypted_state: [[u8; 32]; AUCTION_STATE_CIPHERTEXT_COUNT],
+    pub nonce: u128,
+    /// Set once init_auction_state has produced the encrypted empty state
+    pub initialized: bool,
+    /// The queued submit_bid computation, if any; its callback must land before the next bid
+    pub pending_computation: Option<Pubkey>,
+    /// Slot pending_computation was set at; release_stale_computation clears it after a timeout
+    pub pending_since_slot: u64,
+    /// Copied from the sealed_auction Auction: reveal floors the second price at the reserve
+    pub second_price: bool,
+    /// BidWithdrawal receipts already applied by retract_bid
+    pub retracted_count: u64,
+}
+
+impl AuctionBidState {
+    /// Store MXE ciphertexts of AuctionState in field order
+    fn store_encrypted_state(&mut self, ciphertexts: &[[u8; 32]], nonce: u128) {
+        self.encrypted_state.copy_from_slice(&ciphertexts[..AUCTION_STATE_CIPHERTEXT_COUNT]);
+        self.nonce = nonce;
+    }
+}
+
+/// Encrypted ranking of a multi-unit auction, in AuctionState field order
+#[account]
+#[derive(InitSpace)]
+pub struct MultiUnitBidState {
+    pub auction_id: Pubkey,
+    pub bump: u8,
+    pub bid_count: u64,
+    pub encrypted_state: [[u8; 32]; AUCTION_STATE_CIPHERTEXT_COUNT],
+    pub nonce: u128,
+    pub initialized: bool,
+    pub pending_computation: Option<Pubkey>,
+    pub pending_since_slot: u64,
+    pub second_price: bool,
+    /// Copied from the sealed_auction Auction
+    pub units: u8,
+}
+
+impl MultiUnitBidState {
+    fn store_encrypted_state(&mut self, ciphertexts: &[[u8; 32]], nonce: u128) {
+        self.encrypted_state.copy_from_slice(&ciphertexts[..AUCTION_STATE_CIPHERTEXT_COUNT]);
+        self.nonce = nonce;
+    }
+}
+
+/// Mirror of sealed_auction's AuctionType
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuctionType {
+    FirstPrice,
+    SecondPrice,
+}
+
+/// Mirror of sealed_auction's AssetKind
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    Spl,
+    ProgrammableNft,
+    Compressed,
+    Core,
+}
+
+/// Mirror of sealed_auction's CompressedNft
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CompressedNft {
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
+/// Mirror of sealed_auction's SoftClose
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SoftClose {
+    pub window: i64,
+    pub extension: i64,
+    pub max_end_time: i64,
+}
+
+/// Mirror of sealed_auction's Auction (cross-program account - we read it but don't own it)
+#[account]
+pub struct Auction {
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub listing_id: u64,
+    pub min_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub settled: bool,
+    pub bid_count: u64,
+    pub bump: u8,
+    pub auction_type: AuctionType,
+    pub payment_mint: Option<Pubkey>,
+    pub hidden_reserve: bool,
+    pub resolution_grace_period: i64,
+    pub expired: bool,
+    pub withdrawal_count: u64,
+    pub soft_close: Option<SoftClose>,
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub asset_kind: AssetKind,
+    pub compressed: Option<CompressedNft>,
+    pub units: u8,
+    pub unit_amount: u64,
+    pub units_claimed: u8,
+    pub bundle_items: u8,
+    pub slash_bps: u16,
+}
+
+/// Mirror of sealed_auction's BidEscrow (cross-program account - we read it but don't own it)
+#[account]
+pub struct BidEscrow {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub max_locked_amount: u64,
+    pub withdrawn: bool,
+    pub bump: u8,
+    pub slashed: bool,
+    pub locked_slot: u64,
+}
+
+/// Mirror of sealed_auction's BidWithdrawal (cross-program account - we read it but don't own it)
+#[account]
+pub struct BidWithdrawal {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub index: u64,
+    pub bump: u8,
+    pub locked_slot: u64,
+}
+
+/// Stores the PLAINTEXT auction result after reveal
+/// This is what sealed_auction reads to verify the winner
+#[account]
+#[derive(InitSpace)]
+pub struct AuctionResult {
+    /// The auction this result belongs to
+    pub auction_id: Pubkey,
+    /// PDA bump
+    pub bump: u8,
+    /// Whether the result has been revealed
+    pub revealed: bool,
+    /// The winner's pubkey (plaintext)
+    pub winner: Pubkey,
+    /// The winning bid amount in lamports (plaintext);
+    /// the uniform clearing price for multi-unit auctions
+    pub winning_amount: u64,
+    /// Timestamp when revealed
+    pub revealed_at: i64,
+    /// The second-price (Vickrey) clearing price: the second-highest valid bid
+    /// floored at the reserve. Always 0 for first-price auctions, so the
+    /// runner-up's bid is never revealed when settlement doesn't use it.
+    pub second_amount: u64,
+    /// Number of reveal computations queued (reveal_winner + retry_reveal)
+    pub attempts: u32,
+    /// Offset of the most recently queued reveal computation
+    pub last_computation_offset: u64,
+    /// Multi-unit winners, highest bid first; the first `winner_count` are set.
+    /// Single-unit results hold the winner (if any) in winners[0] too.
+    pub winner_count: u8,
+    pub winners: [Pubkey; MAX_UNITS],
+    /// Bidders who bid above their locked collateral; the first
+    /// `defaulter_count` are set and sealed_auction may slash them
+    pub defaulter_count: u8,
+    pub defaulters: [Pubkey; MAX_DEFAULTERS],
+}
+
+impl AuctionResult {
+    /// Store the revealed over-collateral bidders as pubkeys
+    fn store_defaulters(&mut self, count: u64, bidders: impl Iterator<Item = [u64; 4]>) {
+        let count = (count as usize).min(MAX_DEFAULTERS);
+        self.defaulters = [Pubkey::default(); MAX_DEFAULTERS];
+        for (i, bidder) in bidders.take(count).enumerate() {
+            self.defaulters[i] = reconstruct_pubkey(bidder[0], bidder[1], bidder[2], bidder[3]);
+        }
+        self.defaulter_count = count as u8;
+    }
+}
+
+// ============================================================================
+// Instruction Account Structs
+// ============================================================================
+
+#[queue_computation_accounts("init_auction_state", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, auction_id: Pubkey)]
+pub struct InitializeAuctionState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_AUCTION_STATE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AuctionBidState::INIT_SPACE,
+        seeds = [AUCTION_STATE_SEED, auction_id.as_ref()],
+        bump,
+    )]
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+
+    /// CHECK: The sealed_auction Auction this state belongs to.
+    /// Address checked here, owner and contents verified in the handler.
+    #[account(address = auction_id)]
+    pub auction: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("init_auction_state")]
+#[derive(Accounts)]
+pub struct InitAuctionStateCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_AUCTION_STATE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+}
+
+#[queue_computation_accounts("submit_bid", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitBid<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_BID))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    #[account(mut)]
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+
+    /// CHECK: The payer's sealed_auction BidEscrow for this auction.
+    /// PDA verified here, owner and contents verified in the handler.
+    #[account(
+        seeds = [BID_ESCROW_SEED, auction_bid_state.auction_id.as_ref(), payer.key().as_ref()],
+        bump,
+        seeds::program = SEALED_AUCTION_PROGRAM_ID,
+    )]
+    pub bid_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: The sealed_auction Auction this state belongs to.
+    /// Address checked here, owner and contents verified in the handler.
+    #[account(address = auction_bid_state.auction_id @ ErrorCode::AuctionMismatch)]
+    pub auction: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("submit_bid")]
+#[derive(Accounts)]
+pub struct SubmitBidCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_BID))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+}
+
+#[queue_computation_accounts("retract_bid", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RetractBid<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RETRACT_BID))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    #[account(mut)]
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+
+    /// CHECK: The next unprocessed sealed_auction BidWithdrawal receipt.
+    /// PDA verified here, owner and contents verified in the handler.
+    #[account(
+        seeds = [
+            BID_WITHDRAWAL_SEED,
+            auction_bid_state.auction_id.as_ref(),
+            auction_bid_state.retracted_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+        seeds::program = SEALED_AUCTION_PROGRAM_ID,
+    )]
+    pub bid_withdrawal: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("retract_bid")]
+#[derive(Accounts)]
+pub struct RetractBidCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RETRACT_BID))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseStaleComputation<'info> {
+    #[account(mut)]
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+}
+
+#[queue_computation_accounts("reveal_winner", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealWinner<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_WINNER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    /// The auction's encrypted state account
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+
+    /// CHECK: The sealed_auction Auction this state belongs to.
+    /// Address checked here, owner and contents verified in the handler.
+    #[account(address = auction_bid_state.auction_id @ ErrorCode::AuctionMismatch)]
+    pub auction: UncheckedAccount<'info>,
+
+    /// The auction result account (created here, written in callback)
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AuctionResult::INIT_SPACE,
+        seeds = [AUCTION_RESULT_SEED, auction_bid_state.auction_id.as_ref()],
+        bump,
+    )]
+    pub auction_result: Account<'info, AuctionResult>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[queue_computation_accounts("reveal_winner", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RetryReveal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_WINNER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    /// The auction's encrypted state account
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+
+    /// The existing, still unrevealed result (only created once reveal_winner passed the end time)
+    #[account(
+        mut,
+        seeds = [AUCTION_RESULT_SEED, auction_bid_state.auction_id.as_ref()],
+        bump = auction_result.bump,
+        constraint = !auction_result.revealed @ ErrorCode::AlreadyRevealed,
+    )]
+    pub auction_result: Account<'info, AuctionResult>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("reveal_winner")]
+#[derive(Accounts)]
+pub struct RevealWinnerCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_WINNER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    /// The auction bid state (read-only)
+    pub auction_bid_state: Account<'info, AuctionBidState>,
+
+    /// The auction result account (writable - stores plaintext result)
+    #[account(mut)]
+    pub auction_result: Account<'info, AuctionResult>,
+}
+
+#[init_computation_definition_accounts("init_auction_state", payer)]
+#[derive(Accounts)]
+pub struct InitInitAuctionStateCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("submit_bid", payer)]
+#[derive(Accounts)]
+pub struct InitSubmitBidCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
 
-        // Store plaintext result for settlement
-        let result = &mut ctx.accounts.auction_result;
-        result.winner = winner;
-        result.winning_amount = o.field_0;
-        result.revealed_at = Clock::get()?.unix_timestamp;
-        result.revealed = true;
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
 
-        // Emit event for indexers
-        emit!(AuctionResultEvent {
-            auction_id: ctx.accounts.auction_bid_state.auction_id,
-            winning_bid: o.field_0,
-            winner,
-        });
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
 
-        Ok(())
-    }
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
 }
 
-// ============================================================================
-// Account Structs
-// ============================================================================
+#[init_computation_definition_accounts("reveal_winner", payer)]
+#[derive(Accounts)]
+pub struct InitRevealWinnerCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
 
-/// Stores encrypted auction state between bids
-#[account]
-#[derive(InitSpace)]
-pub struct AuctionBidState {
-    pub auction_id: Pubkey,
-    pub bump: u8,
-    pub bid_count: u64,
-    pub encrypted_max_bid: [u8; 32],
-    pub encrypted_winner_0: [u8; 32],
-    pub encrypted_winner_1: [u8; 32],
-    pub encrypted_winner_2: [u8; 32],
-    pub encrypted_winner_3: [u8; 32],
-    pub nonce: u128,
-}
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
 
-/// Stores the PLAINTEXT auction result after reveal
-/// This is what sealed_auction reads to verify the winner
-#[account]
-#[derive(InitSpace)]
-pub struct AuctionResult {
-    /// The auction this result belongs to
-    pub auction_id: Pubkey,
-    /// PDA bump
-    pub bump: u8,
-    /// Whether the result has been revealed
-    pub revealed: bool,
-    /// The winner's pubkey (plaintext)
-    pub winner: Pubkey,
-    /// The winning bid amount in lamports (plaintext)
-    pub winning_amount: u64,
-    /// Timestamp when revealed
-    pub revealed_at: i64,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
 }
 
-// ============================================================================
-// Instruction Account Structs
-// ============================================================================
+#[init_computation_definition_accounts("retract_bid", payer)]
+#[derive(Accounts)]
+pub struct InitRetractBidCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
+#[queue_computation_accounts("init_multi_unit_state", payer)]
 #[derive(Accounts)]
-#[instruction(auction_id: Pubkey)]
-pub struct InitializeAuctionState<'info> {
+#[instruction(computation_offset: u64, auction_id: Pubkey)]
+pub struct InitializeMultiUnitState<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MULTI_UNIT_STATE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
     #[account(
         init,
         payer = payer,
-        space = 8 + AuctionBidState::INIT_SPACE,
-        seeds = [AUCTION_STATE_SEED, auction_id.as_ref()],
+        space = 8 + MultiUnitBidState::INIT_SPACE,
+        seeds = [MULTI_UNIT_STATE_SEED, auction_id.as_ref()],
         bump,
     )]
-    pub auction_bid_state: Account<'info, AuctionBidState>,
+    pub multi_unit_bid_state: Box<Account<'info, MultiUnitBidState>>,
+
+    /// CHECK: The sealed_auction Auction this state belongs to.
+    /// Address checked here, owner and contents verified in the handler.
+    #[account(address = auction_id)]
+    pub auction: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
 }
 
-#[queue_computation_accounts("submit_bid", payer)]
+#[callback_accounts("init_multi_unit_state")]
+#[derive(Accounts)]
+pub struct InitMultiUnitStateCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MULTI_UNIT_STATE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub multi_unit_bid_state: Box<Account<'info, MultiUnitBidState>>,
+}
+
+#[queue_computation_accounts("submit_multi_unit_bid", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct SubmitBid<'info> {
+pub struct SubmitMultiUnitBid<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -345,7 +1795,7 @@ pub struct SubmitBid<'info> {
     /// CHECK: computation_account
     pub computation_account: UncheckedAccount<'info>,
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_BID))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_MULTI_UNIT_BID))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
 
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
@@ -358,18 +1808,32 @@ pub struct SubmitBid<'info> {
     pub clock_account: Account<'info, ClockAccount>,
 
     #[account(mut)]
-    pub auction_bid_state: Account<'info, AuctionBidState>,
+    pub multi_unit_bid_state: Box<Account<'info, MultiUnitBidState>>,
+
+    /// CHECK: The payer's sealed_auction BidEscrow for this auction.
+    /// PDA verified here, owner and contents verified in the handler.
+    #[account(
+        seeds = [BID_ESCROW_SEED, multi_unit_bid_state.auction_id.as_ref(), payer.key().as_ref()],
+        bump,
+        seeds::program = SEALED_AUCTION_PROGRAM_ID,
+    )]
+    pub bid_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: The sealed_auction Auction this state belongs to.
+    /// Address checked here, owner and contents verified in the handler.
+    #[account(address = multi_unit_bid_state.auction_id @ ErrorCode::AuctionMismatch)]
+    pub auction: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[callback_accounts("submit_bid")]
+#[callback_accounts("submit_multi_unit_bid")]
 #[derive(Accounts)]
-pub struct SubmitBidCallback<'info> {
+pub struct SubmitMultiUnitBidCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_BID))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_MULTI_UNIT_BID))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
 
     #[account(address = derive_mxe_pda!())]
@@ -386,13 +1850,19 @@ pub struct SubmitBidCallback<'info> {
     pub instructions_sysvar: AccountInfo<'info>,
 
     #[account(mut)]
-    pub auction_bid_state: Account<'info, AuctionBidState>,
+    pub multi_unit_bid_state: Box<Account<'info, MultiUnitBidState>>,
 }
 
-#[queue_computation_accounts("reveal_winner", payer)]
+#[derive(Accounts)]
+pub struct ReleaseStaleMultiUnitComputation<'info> {
+    #[account(mut)]
+    pub multi_unit_bid_state: Box<Account<'info, MultiUnitBidState>>,
+}
+
+#[queue_computation_accounts("reveal_multi_unit", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct RevealWinner<'info> {
+pub struct RevealMultiUnit<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -421,7 +1891,7 @@ pub struct RevealWinner<'info> {
     /// CHECK: computation_account
     pub computation_account: UncheckedAccount<'info>,
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_WINNER))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_MULTI_UNIT))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
 
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
@@ -434,14 +1904,19 @@ pub struct RevealWinner<'info> {
     pub clock_account: Account<'info, ClockAccount>,
 
     /// The auction's encrypted state account
-    pub auction_bid_state: Account<'info, AuctionBidState>,
+    pub multi_unit_bid_state: Box<Account<'info, MultiUnitBidState>>,
+
+    /// CHECK: The sealed_auction Auction this state belongs to.
+    /// Address checked here, owner and contents verified in the handler.
+    #[account(address = multi_unit_bid_state.auction_id @ ErrorCode::AuctionMismatch)]
+    pub auction: UncheckedAccount<'info>,
 
     /// The auction result account (created here, written in callback)
     #[account(
         init,
         payer = payer,
         space = 8 + AuctionResult::INIT_SPACE,
-        seeds = [AUCTION_RESULT_SEED, auction_bid_state.auction_id.as_ref()],
+        seeds = [AUCTION_RESULT_SEED, multi_unit_bid_state.auction_id.as_ref()],
         bump,
     )]
     pub auction_result: Account<'info, AuctionResult>,
@@ -450,12 +1925,72 @@ pub struct RevealWinner<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[callback_accounts("reveal_winner")]
+#[queue_computation_accounts("reveal_multi_unit", payer)]
 #[derive(Accounts)]
-pub struct RevealWinnerCallback<'info> {
+#[instruction(computation_offset: u64)]
+pub struct RetryRevealMultiUnit<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_MULTI_UNIT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    /// The auction's encrypted state account
+    pub multi_unit_bid_state: Box<Account<'info, MultiUnitBidState>>,
+
+    /// The existing, still unrevealed result (only created once reveal_multi_unit passed the end time)
+    #[account(
+        mut,
+        seeds = [AUCTION_RESULT_SEED, multi_unit_bid_state.auction_id.as_ref()],
+        bump = auction_result.bump,
+        constraint = !auction_result.revealed @ ErrorCode::AlreadyRevealed,
+    )]
+    pub auction_result: Account<'info, AuctionResult>,
+
+    pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+}
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_WINNER))]
+#[callback_accounts("reveal_multi_unit")]
+#[derive(Accounts)]
+pub struct RevealMultiUnitCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_MULTI_UNIT))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
 
     #[account(address = derive_mxe_pda!())]
@@ -472,16 +2007,16 @@ pub struct RevealWinnerCallback<'info> {
     pub instructions_sysvar: AccountInfo<'info>,
 
     /// The auction bid state (read-only)
-    pub auction_bid_state: Account<'info, AuctionBidState>,
+    pub multi_unit_bid_state: Box<Account<'info, MultiUnitBidState>>,
 
     /// The auction result account (writable - stores plaintext result)
     #[account(mut)]
     pub auction_result: Account<'info, AuctionResult>,
 }
 
-#[init_computation_definition_accounts("submit_bid", payer)]
+#[init_computation_definition_accounts("init_multi_unit_state", payer)]
 #[derive(Accounts)]
-pub struct InitSubmitBidCompDef<'info> {
+pub struct InitInitMultiUnitStateCompDef<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -496,9 +2031,26 @@ pub struct InitSubmitBidCompDef<'info> {
     pub system_program: Program<'info, System>,
 }
 
-#[init_computation_definition_accounts("reveal_winner", payer)]
+#[init_computation_definition_accounts("submit_multi_unit_bid", payer)]
 #[derive(Accounts)]
-pub struct InitRevealWinnerCompDef<'info> {
+pub struct InitSubmitMultiUnitBidCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_multi_unit", payer)]
+#[derive(Accounts)]
+pub struct InitRevealMultiUnitCompDef<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -521,6 +2073,7 @@ pub struct InitRevealWinnerCompDef<'info> {
 pub struct AuctionUpdatedEvent {
     pub auction_id: Pubkey,
     pub new_max_bid: [u8; 32],
+    pub new_second_bid: [u8; 32],
     pub new_winner_0: [u8; 32],
     pub new_winner_1: [u8; 32],
     pub new_winner_2: [u8; 32],
@@ -529,11 +2082,34 @@ pub struct AuctionUpdatedEvent {
     pub bid_count: u64,
 }
 
+#[event]
+pub struct BidRetractedEvent {
+    pub auction_id: Pubkey,
+    pub retracted_count: u64,
+}
+
+#[event]
+pub struct MultiUnitUpdatedEvent {
+    pub auction_id: Pubkey,
+    pub nonce: u128,
+    pub bid_count: u64,
+}
+
+#[event]
+pub struct MultiUnitResultEvent {
+    pub auction_id: Pubkey,
+    pub clearing_price: u64,
+    pub winners: Vec<Pubkey>,
+    pub defaulters: Vec<Pubkey>,
+}
+
 #[event]
 pub struct AuctionResultEvent {
     pub auction_id: Pubkey,
     pub winning_bid: u64,
+    pub second_bid: u64,
     pub winner: Pubkey,
+    pub defaulters: Vec<Pubkey>,
 }
 
 // ============================================================================
@@ -546,4 +2122,38 @@ pub enum ErrorCode {
     AbortedComputation,
     #[msg("Cluster not set")]
     ClusterNotSet,
+    #[msg("Account is not owned by the sealed_auction program")]
+    InvalidSealedAuctionAccount,
+    #[msg("Signer is not the bidder of this escrow")]
+    BidderMismatch,
+    #[msg("Account belongs to a different auction")]
+    AuctionMismatch,
+    #[msg("Only the auction seller can do this")]
+    NotSeller,
+    #[msg("Auction has not started yet")]
+    AuctionNotStarted,
+    #[msg("Auction has already ended")]
+    AuctionEnded,
+    #[msg("Auction has not ended yet")]
+    AuctionNotEnded,
+    #[msg("Auction result has already been revealed")]
+    AlreadyRevealed,
+    #[msg("Bid escrow has already been withdrawn")]
+    EscrowWithdrawn,
+    #[msg("Encrypted auction state has not been initialized yet")]
+    AuctionStateNotInitialized,
+    #[msg("A bid computation is still pending for this auction")]
+    ComputationPending,
+    #[msg("Callback does not match the pending computation")]
+    UnexpectedComputation,
+    #[msg("A bid withdrawal must be retracted from the encrypted state first")]
+    RetractionPending,
+    #[msg("Bid withdrawal receipt is not the next one to process")]
+    UnexpectedWithdrawal,
+    #[msg("Auction unit count does not match this bid state")]
+    UnitsMismatch,
+    #[msg("No computation is pending for this auction")]
+    NoPendingComputation,
+    #[msg("The pending computation has not timed out yet")]
+    ComputationNotStale,
 }
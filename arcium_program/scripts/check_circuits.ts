@@ -0,0 +1,56 @@
+/**
+ * Check that build/ has an up-to-date .arcis and .hash for every computation
+ * definition in programs/arcium_program. The circuit URLs point at
+ * build/<name>.arcis on main and `circuit_hash!` embeds build/<name>.hash,
+ * so both must be regenerated with `arcium build` and committed whenever a
+ * circuit in encrypted-ixs changes.
+ */
+import { createHash } from "crypto";
+import fs from "fs";
+import path from "path";
+
+function main() {
+    const root = path.join(__dirname, "..");
+    const program = fs.readFileSync(path.join(root, "programs", "arcium_program", "src", "lib.rs")).toString();
+    const circuits = fs.readFileSync(path.join(root, "encrypted-ixs", "src", "lib.rs")).toString();
+
+    const compDefs = [...program.matchAll(/comp_def_offset\("(\w+)"\)/g)].map((m) => m[1]);
+    const instructions = new Set([...circuits.matchAll(/#\[instruction\]\s*pub fn (\w+)/g)].map((m) => m[1]));
+
+    let failed = false;
+    for (const name of compDefs) {
+        const problems: string[] = [];
+        const arcis = path.join(root, "build", `${name}.arcis`);
+        const hash = path.join(root, "build", `${name}.hash`);
+
+        if (!instructions.has(name)) {
+            problems.push("no #[instruction] of that name in encrypted-ixs");
+        }
+        if (!fs.existsSync(arcis)) {
+            problems.push(`missing build/${name}.arcis`);
+        }
+        if (!fs.existsSync(hash)) {
+            problems.push(`missing build/${name}.hash`);
+        }
+        if (problems.length === 0) {
+            const digest = [...createHash("sha256").update(fs.readFileSync(arcis)).digest()];
+            if (JSON.stringify(digest) !== JSON.stringify(JSON.parse(fs.readFileSync(hash).toString()))) {
+                problems.push(`build/${name}.hash does not match build/${name}.arcis`);
+            }
+        }
+
+        if (problems.length > 0) {
+            failed = true;
+            console.log(`❌ ${name}: ${problems.join(", ")}`);
+        } else {
+            console.log(`✅ ${name}`);
+        }
+    }
+
+    if (failed) {
+        console.log("\nRun `arcium build` and commit build/ before deploying or initializing comp defs.");
+        process.exit(1);
+    }
+}
+
+main();
@@ -48,12 +48,23 @@ async function main() {
     console.log("MXE Account:", mxeAddress.toBase58());
     console.log("Cluster Account:", clusterAddress.toBase58());
 
-    // 3. Initialize 'submit_bid' Computation Definition
+    // 3. Initialize 'init_auction_state' Computation Definition
+    await initCompDef(program, wallet.payer, "init_auction_state");
+
+    // 4. Initialize 'submit_bid' Computation Definition
     await initCompDef(program, wallet.payer, "submit_bid");
 
-    // 4. Initialize 'reveal_winner' Computation Definition
+    // 5. Initialize 'reveal_winner' Computation Definition
     await initCompDef(program, wallet.payer, "reveal_winner");
 
+    // 6. Initialize 'retract_bid' Computation Definition
+    await initCompDef(program, wallet.payer, "retract_bid");
+
+    // 7. Initialize multi-unit Computation Definitions
+    await initCompDef(program, wallet.payer, "init_multi_unit_state");
+    await initCompDef(program, wallet.payer, "submit_multi_unit_bid");
+    await initCompDef(program, wallet.payer, "reveal_multi_unit");
+
     console.log("\n--- CONFIGURATION FOR FRONTEND ---");
     console.log(`export const ARCIUM_CONFIG = {`);
     console.log(`  mxe: new PublicKey("${mxeAddress.toBase58()}"),`);
@@ -69,8 +80,13 @@ async function initCompDef(
     compDefName: string
 ) {
     const methodMap: Record<string, string> = {
+        "init_auction_state": "initInitAuctionStateCompDef",
         "submit_bid": "initSubmitBidCompDef",
-        "reveal_winner": "initRevealWinnerCompDef"
+        "reveal_winner": "initRevealWinnerCompDef",
+        "retract_bid": "initRetractBidCompDef",
+        "init_multi_unit_state": "initInitMultiUnitStateCompDef",
+        "submit_multi_unit_bid": "initSubmitMultiUnitBidCompDef",
+        "reveal_multi_unit": "initRevealMultiUnitCompDef"
     };
     const methodName = methodMap[compDefName];
     if (!methodName) throw new Error(`Unknown comp def: ${compDefName}`);
@@ -34,7 +34,8 @@ async function main() {
 
     // 3. Initialize ONLY CompDefs
     const program = new anchor.Program(arciumProgramIdl as any, provider);
-    const compDefs = ["submit_bid", "reveal_winner"];
+    const compDefs = ["init_auction_state", "submit_bid", "reveal_winner", "retract_bid",
+        "init_multi_unit_state", "submit_multi_unit_bid", "reveal_multi_unit"];
 
     for (const name of compDefs) {
         console.log(`\n⚙️ Registering CompDef: ${name}...`);
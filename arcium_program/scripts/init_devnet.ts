@@ -107,7 +107,8 @@ async function main() {
     // 4. Initialize Computation Definitions (CompDefs)
     const program = new anchor.Program(arciumProgramIdl as any, provider);
 
-    const compDefs = ["submit_bid", "reveal_winner"];
+    const compDefs = ["init_auction_state", "submit_bid", "reveal_winner", "retract_bid",
+        "init_multi_unit_state", "submit_multi_unit_bid", "reveal_multi_unit"];
 
     for (const name of compDefs) {
         console.log(`\n⚙️ Initializing CompDef: ${name}...`);
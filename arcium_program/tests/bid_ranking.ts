@@ -0,0 +1,647 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { ArciumProgram } from "../target/types/arcium_program";
+import { randomBytes } from "crypto";
+import {
+  awaitComputationFinalization,
+  getArciumEnv,
+  getCompDefAccOffset,
+  getArciumAccountBaseSeed,
+  getArciumProgramId,
+  RescueCipher,
+  deserializeLE,
+  getMXEPublicKey,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getClusterAccAddress,
+  x25519,
+} from "@arcium-hq/client";
+import {
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import * as fs from "fs";
+import { expect } from "chai";
+
+// sealed_auction is loaded at genesis (see Anchor.toml); build it first
+const sealedIdl = JSON.parse(
+  fs.readFileSync("../sealed_auction/target/idl/sealed_auction.json").toString(),
+);
+
+const LAMPORTS = anchor.web3.LAMPORTS_PER_SOL;
+
+describe("Bid ranking", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace
+    .ArciumProgram as Program<ArciumProgram>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const sealed = new anchor.Program(sealedIdl, provider);
+  const seller = provider.wallet.publicKey;
+
+  const arciumEnv = getArciumEnv();
+  const clusterAccount = getClusterAccAddress(arciumEnv.arciumClusterOffset);
+
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    sealed.programId,
+  );
+
+  let mxePublicKey: Uint8Array;
+  let listingId = 0;
+
+  before(async () => {
+    for (const name of [
+      "init_auction_state",
+      "submit_bid",
+      "retract_bid",
+      "reveal_winner",
+    ]) {
+      await initCompDef(name);
+    }
+    mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    if (!(await provider.connection.getAccountInfo(configPda))) {
+      const [programData] = PublicKey.findProgramAddressSync(
+        [sealed.programId.toBuffer()],
+        new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111"),
+      );
+      await sealed.methods
+        .initializeConfig(seller, 0, seller, 0)
+        .accountsPartial({
+          authority: seller,
+          config: configPda,
+          program: sealed.programId,
+          programData,
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+  });
+
+  it("Replaces a retracted leader with the runner-up, priced by the third bid", async () => {
+    const alice = await fundedKeypair();
+    const bob = await fundedKeypair();
+    const carol = await fundedKeypair();
+
+    const auction = await createAuction(0.1 * LAMPORTS, 45);
+    await initializeAuctionState(auction);
+
+    await lockAndBid(auction, alice, 0.3 * LAMPORTS);
+    await lockAndBid(auction, bob, 0.2 * LAMPORTS);
+    await lockAndBid(auction, carol, 0.1 * LAMPORTS);
+
+    // The leader pulls out; retract_bid runs before bidding ends
+    const receipt = await withdrawBid(auction, alice);
+    await retractBid(auction);
+    const receiptAccount = await sealed.account.bidWithdrawal.fetch(receipt);
+    expect(receiptAccount.bidder.equals(alice.publicKey)).to.equal(true);
+
+    const result = await revealWinner(auction);
+    expect(result.winner.equals(bob.publicKey)).to.equal(true);
+    expect(result.winningAmount.toNumber()).to.equal(0.2 * LAMPORTS);
+    expect(result.secondAmount.toNumber()).to.equal(0.1 * LAMPORTS);
+
+    // The processed receipt's rent goes back to the withdrawn bidder
+    await sealed.methods
+      .closeBidWithdrawal()
+      .accountsPartial({
+        bidder: alice.publicKey,
+        bidWithdrawal: receipt,
+        auctionResult: auctionResultPda(auction),
+        auction,
+      })
+      .rpc({ commitment: "confirmed" });
+    expect(await provider.connection.getAccountInfo(receipt)).to.equal(null);
+  });
+
+  it("Gives an equal bid to the escrow locked in the earlier slot", async () => {
+    const early = await fundedKeypair();
+    const late = await fundedKeypair();
+
+    const auction = await createAuction(0.1 * LAMPORTS, 40);
+    await initializeAuctionState(auction);
+
+    await lockFunds(auction, [early], 0.5 * LAMPORTS);
+    await waitForNextSlot();
+    await lockFunds(auction, [late], 0.5 * LAMPORTS);
+    const earlySlot = await lockedSlot(auction, early);
+    const lateSlot = await lockedSlot(auction, late);
+    expect(earlySlot).to.be.lessThan(lateSlot);
+
+    // The later escrow bids first; submission order must not matter
+    await submitBid(auction, late, 0.25 * LAMPORTS);
+    await submitBid(auction, early, 0.25 * LAMPORTS);
+
+    const result = await revealWinner(auction);
+    expect(result.winner.equals(early.publicKey)).to.equal(true);
+    expect(result.winningAmount.toNumber()).to.equal(0.25 * LAMPORTS);
+    expect(result.secondAmount.toNumber()).to.equal(0.25 * LAMPORTS);
+  });
+
+  it("Gives an equal bid locked in the same slot to the lower bidder pubkey", async () => {
+    const [low, high] = [await fundedKeypair(), await fundedKeypair()].sort(
+      (a, b) => (pubkeyBelow(a.publicKey, b.publicKey) ? -1 : 1),
+    );
+
+    const auction = await createAuction(0.1 * LAMPORTS, 40);
+    await initializeAuctionState(auction);
+
+    // Both locks land in one transaction, so they share a slot
+    await lockFunds(auction, [high, low], 0.5 * LAMPORTS);
+    expect(await lockedSlot(auction, low)).to.equal(await lockedSlot(auction, high));
+
+    await submitBid(auction, high, 0.25 * LAMPORTS);
+    await submitBid(auction, low, 0.25 * LAMPORTS);
+
+    const result = await revealWinner(auction);
+    expect(result.winner.equals(low.publicKey)).to.equal(true);
+    expect(result.winningAmount.toNumber()).to.equal(0.25 * LAMPORTS);
+  });
+
+  it("Keeps the runner-up's bid sealed in a first-price auction", async () => {
+    const winner = await fundedKeypair();
+    const runnerUp = await fundedKeypair();
+
+    const auction = await createAuction(0.1 * LAMPORTS, 40, { firstPrice: {} });
+    await initializeAuctionState(auction);
+
+    await lockAndBid(auction, winner, 0.3 * LAMPORTS);
+    await lockAndBid(auction, runnerUp, 0.2 * LAMPORTS);
+
+    // The winner pays their own bid, so the runner-up's is never revealed
+    const result = await revealWinner(auction);
+    expect(result.winner.equals(winner.publicKey)).to.equal(true);
+    expect(result.winningAmount.toNumber()).to.equal(0.3 * LAMPORTS);
+    expect(result.secondAmount.toNumber()).to.equal(0);
+  });
+
+  it("Drops bids below a hidden reserve and floors the second price at it", async () => {
+    const winner = await fundedKeypair();
+    const belowReserve = await fundedKeypair();
+
+    const auction = await createAuction(0, 40, { secondPrice: {} }, true);
+    await initializeAuctionState(auction, 0.25 * LAMPORTS);
+
+    await lockAndBid(auction, winner, 0.3 * LAMPORTS);
+    await lockAndBid(auction, belowReserve, 0.2 * LAMPORTS);
+
+    // The 0.2 SOL bid never counts, so the lone valid bid pays the reserve
+    const result = await revealWinner(auction);
+    expect(result.winner.equals(winner.publicKey)).to.equal(true);
+    expect(result.winningAmount.toNumber()).to.equal(0.3 * LAMPORTS);
+    expect(result.secondAmount.toNumber()).to.equal(0.25 * LAMPORTS);
+  });
+
+  it("Drops a bid above its collateral so the winner's escrow always covers the price", async () => {
+    const winner = await fundedKeypair();
+    const overbidder = await fundedKeypair();
+
+    const auction = await createAuction(0.1 * LAMPORTS, 40, { secondPrice: {} });
+    await initializeAuctionState(auction);
+
+    await lockAndBid(auction, winner, 0.3 * LAMPORTS);
+    // 0.5 SOL bid against 0.2 SOL of collateral
+    await lockFunds(auction, [overbidder], 0.2 * LAMPORTS);
+    await submitBid(auction, overbidder, 0.5 * LAMPORTS);
+
+    // The overbid neither wins nor sets the second price (settlement floors the
+    // empty runner-up at min_price); it only marks a defaulter
+    const result = await revealWinner(auction);
+    expect(result.winner.equals(winner.publicKey)).to.equal(true);
+    expect(result.winningAmount.toNumber()).to.equal(0.3 * LAMPORTS);
+    expect(result.secondAmount.toNumber()).to.equal(0);
+    expect(result.defaulterCount).to.equal(1);
+    expect(result.defaulters[0].equals(overbidder.publicKey)).to.equal(true);
+  });
+
+  it("Refuses to reveal before the auction ends", async () => {
+    const bidder = await fundedKeypair();
+
+    const auction = await createAuction(0.1 * LAMPORTS, 60);
+    await initializeAuctionState(auction);
+    await lockAndBid(auction, bidder, 0.3 * LAMPORTS);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await expectError(
+      program.methods
+        .revealWinner(computationOffset)
+        .accountsPartial({
+          ...queueAccounts(computationOffset, "reveal_winner"),
+          payer: seller,
+          auctionBidState: auctionBidStatePda(auction),
+          auction,
+          auctionResult: auctionResultPda(auction),
+        })
+        .rpc({ commitment: "confirmed" }),
+      "AuctionNotEnded",
+    );
+    expect(await provider.connection.getAccountInfo(auctionResultPda(auction))).to.equal(null);
+  });
+
+  it("Refuses to retry a reveal that already landed", async () => {
+    const bidder = await fundedKeypair();
+
+    const auction = await createAuction(0.1 * LAMPORTS, 40);
+    await initializeAuctionState(auction);
+    await lockAndBid(auction, bidder, 0.3 * LAMPORTS);
+
+    const result = await revealWinner(auction);
+    expect(result.revealed).to.equal(true);
+    expect(result.attempts).to.equal(1);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await expectError(
+      program.methods
+        .retryReveal(computationOffset)
+        .accountsPartial({
+          ...queueAccounts(computationOffset, "reveal_winner"),
+          payer: seller,
+          auctionBidState: auctionBidStatePda(auction),
+          auctionResult: auctionResultPda(auction),
+        })
+        .rpc({ commitment: "confirmed" }),
+      "AlreadyRevealed",
+    );
+    const after = await program.account.auctionResult.fetch(auctionResultPda(auction));
+    expect(after.attempts).to.equal(1);
+    expect(after.winner.equals(bidder.publicKey)).to.equal(true);
+  });
+
+  async function initCompDef(name: string): Promise<void> {
+    const offset = getCompDefAccOffset(name);
+    const compDefAccount = PublicKey.findProgramAddressSync(
+      [
+        getArciumAccountBaseSeed("ComputationDefinitionAccount"),
+        program.programId.toBuffer(),
+        offset,
+      ],
+      getArciumProgramId(),
+    )[0];
+    if (await provider.connection.getAccountInfo(compDefAccount)) {
+      return;
+    }
+
+    const method = `init${toPascalCase(name)}CompDef`;
+    await program.methods[method]()
+      .accounts({
+        compDefAccount,
+        payer: seller,
+        mxeAccount: getMXEAccAddress(program.programId),
+      })
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function fundedKeypair(): Promise<anchor.web3.Keypair> {
+    const keypair = anchor.web3.Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(
+      keypair.publicKey,
+      2 * LAMPORTS,
+    );
+    await provider.connection.confirmTransaction(sig, "confirmed");
+    return keypair;
+  }
+
+  /// A SOL auction of a fresh NFT, ending `durationSecs` from now.
+  /// Hidden-reserve auctions take a min_price of 0.
+  async function createAuction(
+    minPrice: number,
+    durationSecs: number,
+    auctionType: object = { secondPrice: {} },
+    hiddenReserve: boolean = false,
+  ): Promise<PublicKey> {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const nftMint = await createMint(provider.connection, payer, seller, null, 0);
+    const sellerNftAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      nftMint,
+      seller,
+    );
+    await mintTo(provider.connection, payer, nftMint, sellerNftAccount.address, payer, 1);
+
+    const id = new anchor.BN(++listingId);
+    const [auction] = PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), nftMint.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+      sealed.programId,
+    );
+    const endTime = new anchor.BN(Math.floor(Date.now() / 1000) + durationSecs);
+
+    await sealed.methods
+      .createAuction(
+        id,
+        new anchor.BN(minPrice),
+        new anchor.BN(0),
+        endTime,
+        auctionType,
+        hiddenReserve,
+        new anchor.BN(86_400),
+        null,
+        null,
+      )
+      .accountsPartial({
+        seller,
+        auction,
+        config: configPda,
+        nftMint,
+        sellerNftAccount: sellerNftAccount.address,
+        nftEscrowAccount: getAssociatedTokenAddressSync(nftMint, auction, true),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+    return auction;
+  }
+
+  /// `reserve` only counts for hidden-reserve auctions; the others
+  /// compare against the public min_price
+  async function initializeAuctionState(
+    auction: PublicKey,
+    reserve: number = 0,
+  ): Promise<void> {
+    const { publicKey, cipher } = newCipher();
+    const reserveNonce = randomBytes(16);
+    const [encryptedReserve] = cipher.encrypt([BigInt(reserve)], reserveNonce);
+
+    await queueAndFinalize((computationOffset) =>
+      program.methods
+        .initializeAuctionState(
+          computationOffset,
+          auction,
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(reserveNonce).toString()),
+          Array.from(encryptedReserve),
+          new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+        )
+        .accountsPartial({
+          ...queueAccounts(computationOffset, "init_auction_state"),
+          payer: seller,
+          auctionBidState: auctionBidStatePda(auction),
+          auction,
+        }),
+    );
+  }
+
+  async function lockAndBid(
+    auction: PublicKey,
+    bidder: anchor.web3.Keypair,
+    amount: number,
+  ): Promise<void> {
+    await lockFunds(auction, [bidder], amount);
+    await submitBid(auction, bidder, amount);
+  }
+
+  /// Locks `collateral` for every bidder in a single transaction
+  async function lockFunds(
+    auction: PublicKey,
+    bidders: anchor.web3.Keypair[],
+    collateral: number,
+  ): Promise<void> {
+    const tx = new anchor.web3.Transaction();
+    for (const bidder of bidders) {
+      tx.add(
+        await sealed.methods
+          .lockBidFunds(new anchor.BN(collateral))
+          .accountsPartial({
+            bidder: bidder.publicKey,
+            auction,
+            bidEscrow: bidEscrowPda(auction, bidder.publicKey),
+          })
+          .instruction(),
+      );
+    }
+    await provider.sendAndConfirm(tx, bidders, { commitment: "confirmed" });
+  }
+
+  async function submitBid(
+    auction: PublicKey,
+    bidder: anchor.web3.Keypair,
+    amount: number,
+  ): Promise<void> {
+    const { publicKey, cipher } = newCipher();
+    const nonce = randomBytes(16);
+    const [encryptedBid] = cipher.encrypt([BigInt(amount)], nonce);
+
+    await queueAndFinalize(
+      (computationOffset) =>
+        program.methods
+          .submitBid(
+            computationOffset,
+            Array.from(publicKey),
+            new anchor.BN(deserializeLE(nonce).toString()),
+            Array.from(encryptedBid),
+          )
+          .accountsPartial({
+            ...queueAccounts(computationOffset, "submit_bid"),
+            payer: bidder.publicKey,
+            auctionBidState: auctionBidStatePda(auction),
+            bidEscrow: bidEscrowPda(auction, bidder.publicKey),
+            auction,
+          }),
+      [bidder],
+    );
+  }
+
+  async function withdrawBid(
+    auction: PublicKey,
+    bidder: anchor.web3.Keypair,
+  ): Promise<PublicKey> {
+    const { withdrawalCount } = await sealed.account.auction.fetch(auction);
+    const [bidWithdrawal] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("bid_withdrawal"),
+        auction.toBuffer(),
+        withdrawalCount.toArrayLike(Buffer, "le", 8),
+      ],
+      sealed.programId,
+    );
+
+    await sealed.methods
+      .withdrawBid()
+      .accountsPartial({
+        bidder: bidder.publicKey,
+        auction,
+        bidEscrow: bidEscrowPda(auction, bidder.publicKey),
+        bidWithdrawal,
+      })
+      .signers([bidder])
+      .rpc({ commitment: "confirmed" });
+    return bidWithdrawal;
+  }
+
+  async function retractBid(auction: PublicKey): Promise<void> {
+    await queueAndFinalize((computationOffset) =>
+      program.methods
+        .retractBid(computationOffset)
+        .accountsPartial({
+          ...queueAccounts(computationOffset, "retract_bid"),
+          payer: seller,
+          auctionBidState: auctionBidStatePda(auction),
+        }),
+    );
+  }
+
+  async function revealWinner(auction: PublicKey) {
+    const { endTime } = await sealed.account.auction.fetch(auction);
+    const waitMs = (endTime.toNumber() + 2) * 1000 - Date.now();
+    if (waitMs > 0) {
+      await new Promise((resolve) => setTimeout(resolve, waitMs));
+    }
+
+    const auctionResult = auctionResultPda(auction);
+    await queueAndFinalize((computationOffset) =>
+      program.methods
+        .revealWinner(computationOffset)
+        .accountsPartial({
+          ...queueAccounts(computationOffset, "reveal_winner"),
+          payer: seller,
+          auctionBidState: auctionBidStatePda(auction),
+          auction,
+          auctionResult,
+        }),
+    );
+    return program.account.auctionResult.fetch(auctionResult);
+  }
+
+  async function queueAndFinalize(
+    build: (computationOffset: anchor.BN) => any,
+    signers: anchor.web3.Keypair[] = [],
+  ): Promise<void> {
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await build(computationOffset)
+      .signers(signers)
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed",
+    );
+  }
+
+  function queueAccounts(computationOffset: anchor.BN, circuit: string) {
+    return {
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset,
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE(),
+      ),
+    };
+  }
+
+  /// Asserts `promise` fails with the program error `code`
+  async function expectError(promise: Promise<unknown>, code: string): Promise<void> {
+    try {
+      await promise;
+    } catch (err) {
+      const logs: string[] = err.logs ?? [];
+      expect(
+        err.error?.errorCode?.code === code || logs.some((line) => line.includes(code)),
+        `expected ${code}, got ${err}`,
+      ).to.equal(true);
+      return;
+    }
+    expect.fail(`expected ${code}`);
+  }
+
+  function newCipher() {
+    const privateKey = x25519.utils.randomSecretKey();
+    const publicKey = x25519.getPublicKey(privateKey);
+    const sharedSecret = x25519.getSharedSecret(privateKey, mxePublicKey);
+    return { publicKey, cipher: new RescueCipher(sharedSecret) };
+  }
+
+  function auctionBidStatePda(auction: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_bid_state"), auction.toBuffer()],
+      program.programId,
+    )[0];
+  }
+
+  async function lockedSlot(
+    auction: PublicKey,
+    bidder: anchor.web3.Keypair,
+  ): Promise<number> {
+    const escrow = await sealed.account.bidEscrow.fetch(
+      bidEscrowPda(auction, bidder.publicKey),
+    );
+    return escrow.lockedSlot.toNumber();
+  }
+
+  async function waitForNextSlot(): Promise<void> {
+    const start = await provider.connection.getSlot("confirmed");
+    while ((await provider.connection.getSlot("confirmed")) <= start) {
+      await new Promise((resolve) => setTimeout(resolve, 200));
+    }
+  }
+
+  function auctionResultPda(auction: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_result"), auction.toBuffer()],
+      program.programId,
+    )[0];
+  }
+
+  function bidEscrowPda(auction: PublicKey, bidder: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("bid_escrow"), auction.toBuffer(), bidder.toBuffer()],
+      sealed.programId,
+    )[0];
+  }
+});
+
+async function getMXEPublicKeyWithRetry(
+  provider: anchor.AnchorProvider,
+  programId: PublicKey,
+  maxRetries: number = 20,
+  retryDelayMs: number = 500,
+): Promise<Uint8Array> {
+  for (let attempt = 1; attempt <= maxRetries; attempt++) {
+    try {
+      const mxePublicKey = await getMXEPublicKey(provider, programId);
+      if (mxePublicKey) {
+        return mxePublicKey;
+      }
+    } catch (error) {
+      console.log(`Attempt ${attempt} failed to fetch MXE public key:`, error);
+    }
+
+    if (attempt < maxRetries) {
+      await new Promise((resolve) => setTimeout(resolve, retryDelayMs));
+    }
+  }
+
+  throw new Error(
+    `Failed to fetch MXE public key after ${maxRetries} attempts`,
+  );
+}
+
+function toPascalCase(str: string) {
+  return str
+    .replace(/_(\w)/g, (g0, g1) => g1.toUpperCase())
+    .replace(/^(\w)/, (g0, g1) => g1.toUpperCase());
+}
+
+/// The circuit's final tie-break: compare the pubkeys as four little-endian
+/// u64 words, first word first
+function pubkeyBelow(a: PublicKey, b: PublicKey): boolean {
+  const [wa, wb] = [a.toBuffer(), b.toBuffer()];
+  for (let i = 0; i < 4; i++) {
+    const [x, y] = [wa.readBigUInt64LE(i * 8), wb.readBigUInt64LE(i * 8)];
+    if (x !== y) {
+      return x < y;
+    }
+  }
+  return false;
+}
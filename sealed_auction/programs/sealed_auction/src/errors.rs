@@ -38,9 +38,105 @@ pub enum AuctionError {
     #[msg("Auction end time must be in the future")]
     InvalidEndTime,
 
-    #[msg("Minimum price must be greater than zero")]
+    #[msg("Auction start time must be before the end time")]
+    InvalidStartTime,
+
+    #[msg("Auction has not started yet")]
+    AuctionNotStarted,
+
+    #[msg("Soft-close window and extension must be positive and the cap at or after the end time")]
+    InvalidSoftClose,
+
+    #[msg("Minimum price must be greater than zero, or zero for hidden-reserve auctions")]
     InvalidMinPrice,
 
     #[msg("No valid winner exists")]
     NoValidWinner,
+
+    #[msg("Payment mint does not match auction")]
+    InvalidPaymentMint,
+
+    #[msg("Token payment accounts are required for this auction")]
+    MissingPaymentAccounts,
+
+    #[msg("Signer is not authorized")]
+    Unauthorized,
+
+    #[msg("Fee exceeds the maximum")]
+    InvalidFeeBps,
+
+    #[msg("Programmable NFT accounts are missing or invalid")]
+    InvalidProgrammableNftAccounts,
+
+    #[msg("Compressed NFT accounts are missing or do not match the auction")]
+    InvalidCompressedNftAccounts,
+
+    #[msg("Core asset accounts are missing or do not match the auction")]
+    InvalidCoreAssetAccounts,
+
+    #[msg("NFT mint and token accounts are required for this auction")]
+    MissingNftAccounts,
+
+    #[msg("Creator royalty accounts are missing or out of order")]
+    InvalidCreatorAccounts,
+
+    #[msg("Protocol fee and royalties exceed the clearing price")]
+    RoyaltyExceedsPrice,
+
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+
+    #[msg("Resolution grace period is too short")]
+    InvalidGracePeriod,
+
+    #[msg("Resolution grace period has not elapsed")]
+    GracePeriodNotElapsed,
+
+    #[msg("Auction result already revealed; settle or finalize instead")]
+    ResultAlreadyRevealed,
+
+    #[msg("Lots need 1 to MAX_UNITS units of a positive amount and an SPL mint")]
+    InvalidLot,
+
+    #[msg("Bids on multi-unit auctions cannot be withdrawn")]
+    WithdrawalNotSupported,
+
+    #[msg("Bids can only be withdrawn from the slot after they were locked")]
+    WithdrawalTooEarly,
+
+    #[msg("Bid withdrawal may not have been retracted yet")]
+    WithdrawalPending,
+
+    #[msg("Bundle accounts are missing or do not match the recorded bundle")]
+    InvalidBundle,
+
+    #[msg("Bundles take at most MAX_BUNDLE_ITEMS extra NFTs")]
+    BundleTooLarge,
+
+    #[msg("Slash share cannot exceed 100%")]
+    InvalidSlashBps,
+
+    #[msg("Bidder was not revealed as bidding above their collateral")]
+    NotADefaulter,
+
+    #[msg("Bid escrow has already been slashed")]
+    AlreadySlashed,
+
+    #[msg("Defaulter must be slashed before being refunded")]
+    SlashPending,
+
+    #[msg("Batch refund entries must be complete groups of a writable bidder and their own escrow accounts")]
+    InvalidRefundBatch,
+
+    #[msg("Releasing a compressed NFT needs the merkle root its proof was fetched against")]
+    MissingCompressedNftRoot,
+
+    #[msg("Bids can no longer be withdrawn once more than RANKED_BIDS have been locked")]
+    WithdrawalsClosed,
+
+    #[msg("The treasury account is required when the auction charges a protocol fee")]
+    MissingTreasury,
+
+    #[msg("Every bundle item must be an NFT: a mint with 0 decimals and a supply of 1")]
+    BundleItemNotNft,
 }
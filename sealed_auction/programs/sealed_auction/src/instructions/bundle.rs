@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TransferChecked};
+use crate::state::*;
+use crate::errors::AuctionError;
+
+/// Move each extra NFT of a new bundle from the seller into its escrow.
+/// `accounts` holds a (mint, seller token account, escrow) triple per item;
+/// escrows are token accounts the seller created with the auction PDA as owner.
+pub fn escrow_bundle<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    primary_mint: &Pubkey,
+    auction: &Pubkey,
+    seller: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<Vec<BundleItem>> {
+    require!(accounts.len() % 3 == 0, AuctionError::InvalidBundle);
+    require!(accounts.len() / 3 <= MAX_BUNDLE_ITEMS, AuctionError::BundleTooLarge);
+
+    let mut items: Vec<BundleItem> = Vec::with_capacity(accounts.len() / 3);
+    for item in accounts.chunks(3) {
+        let (mint_info, source_info, escrow_info) = (&item[0], &item[1], &item[2]);
+        // The whole set moves with one token program
+        require_keys_eq!(*mint_info.owner, token_program.key(), AuctionError::InvalidBundle);
+
+        let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+        require!(mint.decimals == 0 && mint.supply == 1, AuctionError::BundleItemNotNft);
+        let source = InterfaceAccount::<TokenAccount>::try_from(source_info)?;
+        let escrow = InterfaceAccount::<TokenAccount>::try_from(escrow_info)?;
+        require!(
+            mint.key() != *primary_mint && !items.iter().any(|i| i.mint == mint.key()),
+            AuctionError::InvalidBundle
+        );
+        require!(
+            source.owner == seller.key() && source.mint == mint.key(),
+            AuctionError::InvalidBundle
+        );
+        require!(
+            escrow.owner == *auction && escrow.mint == mint.key() && escrow.delegate.is_none(),
+            AuctionError::InvalidBundle
+        );
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program.clone(),
+                TransferChecked {
+                    from: source_info.clone(),
+                    mint: mint_info.clone(),
+                    to: escrow_info.clone(),
+                    authority: seller.clone(),
+                },
+            ),
+            1,
+            mint.decimals,
+        )?;
+
+        items.push(BundleItem {
+            mint: mint.key(),
+            escrow: escrow.key(),
+        });
+    }
+
+    Ok(items)
+}
+
+/// Move every extra NFT of a bundle out of escrow to `recipient`.
+/// `accounts` holds a (mint, escrow, destination token account) triple per item,
+/// in Bundle order; anything after them is ignored.
+pub fn release_bundle<'info>(
+    bundle: &Bundle,
+    accounts: &'info [AccountInfo<'info>],
+    recipient: &Pubkey,
+    auction: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    require!(
+        accounts.len() >= bundle.items.len() * 3,
+        AuctionError::InvalidBundle
+    );
+
+    for (item, infos) in bundle.items.iter().zip(accounts.chunks(3)) {
+        let (mint_info, escrow_info, destination_info) = (&infos[0], &infos[1], &infos[2]);
+        require_keys_eq!(mint_info.key(), item.mint, AuctionError::InvalidBundle);
+        require_keys_eq!(escrow_info.key(), item.escrow, AuctionError::InvalidBundle);
+
+        let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+        let escrow = InterfaceAccount::<TokenAccount>::try_from(escrow_info)?;
+        let destination = InterfaceAccount::<TokenAccount>::try_from(destination_info)?;
+        require!(
+            destination.owner == *recipient && destination.mint == item.mint,
+            AuctionError::InvalidBundle
+        );
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferChecked {
+                    from: escrow_info.clone(),
+                    mint: mint_info.clone(),
+                    to: destination_info.clone(),
+                    authority: auction.clone(),
+                },
+                signer_seeds,
+            ),
+            escrow.amount,
+            mint.decimals,
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
+use crate::instructions::programmable_nft::*;
+use crate::instructions::compressed_nft::*;
+use crate::instructions::core_asset::*;
 
 #[derive(Accounts)]
 pub struct CancelAuction<'info> {
@@ -9,40 +12,47 @@ pub struct CancelAuction<'info> {
 
     #[account(
         mut,
-        seeds = [b"auction", auction.nft_mint.as_ref()],
+        seeds = [b"auction", auction.nft_mint.as_ref(), auction.listing_id.to_le_bytes().as_ref()],
         bump = auction.bump,
         close = seller
     )]
     pub auction: Account<'info, Auction>,
 
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
     #[account(
         mut,
-        token::mint = auction.nft_mint,
-        token::authority = auction
+        token::mint = nft_mint,
+        token::authority = auction,
+        token::token_program = token_program,
     )]
-    pub nft_escrow_account: Account<'info, TokenAccount>,
+    pub nft_escrow_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         mut,
         constraint = seller_nft_account.owner == seller.key(),
         constraint = seller_nft_account.mint == auction.nft_mint
     )]
-    pub seller_nft_account: Account<'info, TokenAccount>,
+    pub seller_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    pub token_program: Program<'info, Token>,
-}
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Only for bundles; the extra NFTs go in remaining accounts
+    #[account(
+        mut,
+        seeds = [b"bundle", auction.key().as_ref()],
+        bump = bundle.bump,
+        close = seller
+    )]
+    pub bundle: Option<Box<Account<'info, Bundle>>>,
+
+    /// Only needed for programmable NFTs
+    pub pnft: ProgrammableNftAccounts<'info>,
+
+    /// Only needed for compressed NFTs; the merkle proof goes in remaining accounts
+    pub cnft: CompressedNftAccounts<'info>,
 
-impl<'info> CancelAuction<'info> {
-    pub fn into_transfer_back_to_seller(
-        &self,
-    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        CpiContext::new(
-            self.token_program.to_account_info(),
-            Transfer {
-                from: self.nft_escrow_account.to_account_info(),
-                to: self.seller_nft_account.to_account_info(),
-                authority: self.auction.to_account_info(),
-            },
-        )
-    }
+    /// Only needed for Metaplex Core assets
+    pub core: CoreAssetAccounts<'info>,
 }
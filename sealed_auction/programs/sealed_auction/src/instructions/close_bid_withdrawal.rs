@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+
+/// Close a BidWithdrawal receipt once arcium_program no longer needs it,
+/// returning its rent to the bidder. Anyone can crank it.
+#[derive(Accounts)]
+pub struct CloseBidWithdrawal<'info> {
+    /// CHECK: Safe because we only transfer lamports to it
+    #[account(mut, address = bid_withdrawal.bidder)]
+    pub bidder: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bid_withdrawal", bid_withdrawal.auction.as_ref(), bid_withdrawal.index.to_le_bytes().as_ref()],
+        bump = bid_withdrawal.bump,
+        close = bidder,
+    )]
+    pub bid_withdrawal: Account<'info, BidWithdrawal>,
+
+    /// Only exists once reveal_winner ran, which waits for every receipt to be retracted
+    #[account(
+        seeds = [AUCTION_RESULT_SEED, bid_withdrawal.auction.as_ref()],
+        bump = auction_result.bump,
+        seeds::program = ARCIUM_PROGRAM_ID,
+    )]
+    pub auction_result: Option<Account<'info, AuctionResult>>,
+
+    /// CHECK: The receipt's auction, needed when there is no result: it must be
+    /// expired or already closed. Contents checked in the handler.
+    #[account(address = bid_withdrawal.auction)]
+    pub auction: UncheckedAccount<'info>,
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use crate::state::*;
+use crate::errors::AuctionError;
+
+/// Anchor discriminator of Bubblegum's `transfer` instruction
+const BUBBLEGUM_TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+
+/// Extra accounts for moving Bubblegum compressed NFTs (cNFTs). The merkle
+/// proof is passed as remaining accounts. Omit all of them for other NFTs.
+#[derive(Accounts)]
+pub struct CompressedNftAccounts<'info> {
+    /// CHECK: Bubblegum tree config PDA of merkle_tree, verified by Bubblegum
+    pub tree_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Concurrent merkle tree holding the leaf, checked against the auction
+    #[account(mut)]
+    pub merkle_tree: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: SPL Noop program
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub log_wrapper: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Bubblegum program
+    #[account(address = BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Option<Program<'info, System>>,
+}
+
+impl<'info> CompressedNftAccounts<'info> {
+    /// Bubblegum asset id of the leaf minted at `nonce` in `merkle_tree`
+    pub fn asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"asset", merkle_tree.as_ref(), nonce.to_le_bytes().as_ref()],
+            &BUBBLEGUM_PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// Move the leaf with Bubblegum's transfer. `leaf_owner` must also be the
+    /// leaf delegate; `signer_seeds` is empty when it signs the transaction itself.
+    /// `root` is the tree root the proof was fetched against; Bubblegum accepts
+    /// any root still in the tree's changelog buffer.
+    pub fn transfer(
+        &self,
+        asset: &CompressedNft,
+        root: [u8; 32],
+        leaf_owner: AccountInfo<'info>,
+        new_leaf_owner: AccountInfo<'info>,
+        proof: &[AccountInfo<'info>],
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let (
+            Some(tree_authority),
+            Some(merkle_tree),
+            Some(log_wrapper),
+            Some(compression_program),
+            Some(bubblegum_program),
+            Some(system_program),
+        ) = (
+            &self.tree_authority,
+            &self.merkle_tree,
+            &self.log_wrapper,
+            &self.compression_program,
+            &self.bubblegum_program,
+            &self.system_program,
+        ) else {
+            return err!(AuctionError::InvalidCompressedNftAccounts);
+        };
+        require_keys_eq!(
+            merkle_tree.key(),
+            asset.merkle_tree,
+            AuctionError::InvalidCompressedNftAccounts
+        );
+
+        let mut data = BUBBLEGUM_TRANSFER_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&root);
+        data.extend_from_slice(&asset.data_hash);
+        data.extend_from_slice(&asset.creator_hash);
+        data.extend_from_slice(&asset.nonce.to_le_bytes());
+        data.extend_from_slice(&asset.index.to_le_bytes());
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(tree_authority.key(), false),
+            AccountMeta::new_readonly(leaf_owner.key(), true),
+            // Leaf delegate
+            AccountMeta::new_readonly(leaf_owner.key(), false),
+            AccountMeta::new_readonly(new_leaf_owner.key(), false),
+            AccountMeta::new(merkle_tree.key(), false),
+            AccountMeta::new_readonly(log_wrapper.key(), false),
+            AccountMeta::new_readonly(compression_program.key(), false),
+            AccountMeta::new_readonly(system_program.key(), false),
+        ];
+        accounts.extend(proof.iter().map(|node| AccountMeta::new_readonly(node.key(), false)));
+
+        let mut account_infos = vec![
+            tree_authority.to_account_info(),
+            leaf_owner.clone(),
+            new_leaf_owner,
+            merkle_tree.to_account_info(),
+            log_wrapper.to_account_info(),
+            compression_program.to_account_info(),
+            system_program.to_account_info(),
+            bubblegum_program.to_account_info(),
+        ];
+        account_infos.extend(proof.iter().cloned());
+
+        invoke_signed(
+            &Instruction {
+                program_id: BUBBLEGUM_PROGRAM_ID,
+                accounts,
+                data,
+            },
+            &account_infos,
+            signer_seeds,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use crate::state::*;
+use crate::errors::AuctionError;
+
+/// Core's TransferV1: discriminator 14, then `compression_proof: None`
+const CORE_TRANSFER_V1_DATA: [u8; 2] = [14, 0];
+
+/// Extra accounts for moving Metaplex Core assets. Omit all of them for other NFTs.
+#[derive(Accounts)]
+pub struct CoreAssetAccounts<'info> {
+    /// CHECK: Core asset, checked against the auction and verified by Core
+    #[account(mut, owner = MPL_CORE_PROGRAM_ID)]
+    pub asset: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Collection of the asset (if any), verified by Core
+    pub collection: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Core program
+    #[account(address = MPL_CORE_PROGRAM_ID)]
+    pub core_program: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Option<Program<'info, System>>,
+}
+
+impl<'info> CoreAssetAccounts<'info> {
+    /// Move the asset held by the auction with Core's TransferV1.
+    pub fn transfer(
+        &self,
+        asset_id: &Pubkey,
+        owner: AccountInfo<'info>,
+        new_owner: AccountInfo<'info>,
+        payer: AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let (Some(asset), Some(core_program), Some(system_program)) =
+            (&self.asset, &self.core_program, &self.system_program)
+        else {
+            return err!(AuctionError::InvalidCoreAssetAccounts);
+        };
+        require_keys_eq!(asset.key(), *asset_id, AuctionError::InvalidCoreAssetAccounts);
+
+        transfer_core_asset(
+            asset.to_account_info(),
+            self.collection.as_ref().map(|c| c.to_account_info()),
+            owner,
+            new_owner,
+            payer,
+            system_program.to_account_info(),
+            core_program.to_account_info(),
+            signer_seeds,
+        )
+    }
+}
+
+/// CPI into Core's TransferV1 with `owner` as the signing authority.
+/// Absent optional accounts are passed as the Core program id, as Core expects.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_core_asset<'info>(
+    asset: AccountInfo<'info>,
+    collection: Option<AccountInfo<'info>>,
+    owner: AccountInfo<'info>,
+    new_owner: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    core_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let collection_key = collection.as_ref().map_or(MPL_CORE_PROGRAM_ID, |c| c.key());
+
+    let accounts = vec![
+        AccountMeta::new(asset.key(), false),
+        AccountMeta::new_readonly(collection_key, false),
+        AccountMeta::new(payer.key(), true),
+        AccountMeta::new_readonly(owner.key(), true),
+        AccountMeta::new_readonly(new_owner.key(), false),
+        AccountMeta::new_readonly(system_program.key(), false),
+        // No log wrapper
+        AccountMeta::new_readonly(MPL_CORE_PROGRAM_ID, false),
+    ];
+
+    let mut account_infos = vec![asset, payer, owner, new_owner, system_program, core_program];
+    account_infos.extend(collection);
+
+    invoke_signed(
+        &Instruction {
+            program_id: MPL_CORE_PROGRAM_ID,
+            accounts,
+            data: CORE_TRANSFER_V1_DATA.to_vec(),
+        },
+        &account_infos,
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 use crate::state::*;
+use crate::instructions::programmable_nft::*;
 
 #[derive(Accounts)]
+#[instruction(listing_id: u64)]
 pub struct CreateAuction<'info> {
     #[account(mut)]
     pub seller:Signer<'info>,
@@ -10,36 +13,60 @@ pub struct CreateAuction<'info> {
     #[account(
         init,
         payer = seller,
-        seeds = [b"auction", nft_mint.key().as_ref()],
+        seeds = [b"auction", nft_mint.key().as_ref(), listing_id.to_le_bytes().as_ref()],
         bump,
-        space = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 1
+        space = Auction::SPACE
     )]
     pub auction: Account<'info, Auction>,
 
-    pub nft_mint: Account<'info, Mint>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL or Token-2022 mint bids are paid in. Omit for SOL auctions.
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
     #[account(mut)]
-    pub seller_nft_account:Account<'info, TokenAccount>,
+    pub seller_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// The auction PDA's ATA for nft_mint. Token Metadata only moves
+    /// programmable NFTs between ATAs, so every listing escrows in one.
     #[account(
         init,
         payer = seller,
-        token::mint = nft_mint,
-        token::authority = auction
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
     )]
-    pub nft_escrow_account: Account<'info, TokenAccount>,
+    pub nft_escrow_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,    
+    /// Token program owning the NFT mint (Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// Only for bundles; the extra NFTs go in remaining accounts
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"bundle", auction.key().as_ref()],
+        bump,
+        space = 8 + 32 + 4 + MAX_BUNDLE_ITEMS * 64 + 1
+    )]
+    pub bundle: Option<Box<Account<'info, Bundle>>>,
 
+    /// Only needed for programmable NFTs
+    pub pnft: ProgrammableNftAccounts<'info>,
 }
 
 impl<'info> CreateAuction<'info> {
-    pub fn into_transfer_to_escrow(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+    pub fn into_transfer_to_escrow(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: self.seller_nft_account.to_account_info(),
+                mint: self.nft_mint.to_account_info(),
                 to: self.nft_escrow_account.to_account_info(),
                 authority: self.seller.to_account_info(),
             },
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::*;
+use crate::instructions::compressed_nft::*;
+
+/// Lists a Bubblegum compressed NFT. The auction PDA is keyed by the asset id
+/// in place of a mint, and becomes the leaf owner instead of holding an escrow.
+#[derive(Accounts)]
+#[instruction(listing_id: u64, compressed: CompressedNft)]
+pub struct CreateCompressedAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"auction", compressed.asset_id.as_ref(), listing_id.to_le_bytes().as_ref()],
+        bump,
+        space = Auction::SPACE
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// SPL or Token-2022 mint bids are paid in. Omit for SOL auctions.
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub cnft: CompressedNftAccounts<'info>,
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::*;
+
+/// Lists a Metaplex Core asset. The auction PDA is keyed by the asset account
+/// and escrows it by becoming the asset owner.
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct CreateCoreAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"auction", asset.key().as_ref(), listing_id.to_le_bytes().as_ref()],
+        bump,
+        space = Auction::SPACE
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Core asset owned by the seller, verified by Core
+    #[account(mut, owner = MPL_CORE_PROGRAM_ID)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: Collection of the asset (if any), verified by Core
+    pub collection: Option<UncheckedAccount<'info>>,
+
+    /// SPL or Token-2022 mint bids are paid in. Omit for SOL auctions.
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: Metaplex Core program
+    #[account(address = MPL_CORE_PROGRAM_ID)]
+    pub core_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
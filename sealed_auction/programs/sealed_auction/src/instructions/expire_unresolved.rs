@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::instructions::programmable_nft::*;
+use crate::instructions::compressed_nft::*;
+use crate::instructions::core_asset::*;
+
+/// Unwind an auction that arcium_program never resolved.
+/// Permissionless - anyone can crank once the grace period has passed.
+#[derive(Accounts)]
+pub struct ExpireUnresolved<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Safe - we verify this matches auction.seller
+    #[account(mut, address = auction.seller)]
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", auction.nft_mint.as_ref(), auction.listing_id.to_le_bytes().as_ref()],
+        bump = auction.bump,
+        // Note: Do NOT close here - auction must remain for refund_loser checks
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// CHECK: The AuctionResult PDA from arcium_program. It may not exist
+    /// (reveal never queued) or may be unrevealed; both are checked in the handler.
+    #[account(
+        seeds = [AUCTION_RESULT_SEED, auction.key().as_ref()],
+        bump,
+        seeds::program = ARCIUM_PROGRAM_ID,
+    )]
+    pub auction_result: UncheckedAccount<'info>,
+
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = auction,
+        token::token_program = token_program,
+    )]
+    pub nft_escrow_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        constraint = seller_nft_account.owner == seller.key(),
+        constraint = seller_nft_account.mint == auction.nft_mint
+    )]
+    pub seller_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Only for bundles; the extra NFTs go in remaining accounts
+    #[account(seeds = [b"bundle", auction.key().as_ref()], bump = bundle.bump)]
+    pub bundle: Option<Box<Account<'info, Bundle>>>,
+
+    /// Only needed for programmable NFTs
+    pub pnft: ProgrammableNftAccounts<'info>,
+
+    /// Only needed for compressed NFTs; the merkle proof goes in remaining accounts
+    pub cnft: CompressedNftAccounts<'info>,
+
+    /// Only needed for Metaplex Core assets
+    pub core: CoreAssetAccounts<'info>,
+}
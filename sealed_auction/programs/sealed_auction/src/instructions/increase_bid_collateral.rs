@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::AuctionError;
+
+
+#[derive(Accounts)]
+pub struct IncreaseBidCollateral<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    // Not mut: a top-up is not a new bid, so bid_count is left alone
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid_escrow.bump,
+        constraint = bid_escrow.bidder == bidder.key(),
+        constraint = bid_escrow.auction == auction.key(),
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+
+    // --- SPL payment auctions only ---
+
+    #[account(
+        constraint = auction.payment_mint == Some(payment_mint.key()) @ AuctionError::InvalidPaymentMint,
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = bidder,
+        token::token_program = payment_token_program,
+    )]
+    pub bidder_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The vault created by lock_bid_funds
+    #[account(
+        mut,
+        seeds = [b"bid_vault", bid_escrow.key().as_ref()],
+        bump,
+        token::mint = payment_mint,
+        token::authority = bid_escrow,
+        token::token_program = payment_token_program,
+    )]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
+use crate::errors::AuctionError;
 
 
 #[derive(Accounts)]
@@ -19,9 +21,38 @@ pub struct LockBidFunds<'info> {
         payer = bidder,
         seeds = [b"bid_escrow", auction.key().as_ref(), bidder.key().as_ref()],
         bump,
-        space = 8 + 32 + 32 + 8 + 1 + 1
+        space = 8 + 32 + 32 + 8 + 1 + 1 + 1 + 8
     )]
     pub bid_escrow: Account<'info, BidEscrow>,
 
+    // --- SPL payment auctions only ---
+
+    #[account(
+        constraint = auction.payment_mint == Some(payment_mint.key()) @ AuctionError::InvalidPaymentMint,
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = bidder,
+        token::token_program = payment_token_program,
+    )]
+    pub bidder_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Token account owned by the bid_escrow PDA that holds the locked tokens
+    #[account(
+        init,
+        payer = bidder,
+        seeds = [b"bid_vault", bid_escrow.key().as_ref()],
+        bump,
+        token::mint = payment_mint,
+        token::authority = bid_escrow,
+        token::token_program = payment_token_program,
+    )]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
@@ -1,17 +1,40 @@
+pub mod programmable_nft;
+pub mod compressed_nft;
+pub mod core_asset;
+pub mod bundle;
+pub mod release_asset;
+pub mod initialize_config;
+pub mod update_config;
 pub mod create_auction;
+pub mod create_compressed_auction;
+pub mod create_core_auction;
 pub mod cancel_auction;
 pub mod lock_bid_funds;
+pub mod increase_bid_collateral;
+pub mod withdraw_bid;
+pub mod close_bid_withdrawal;
 pub mod settle_auction;
 pub mod refund_loser;
+pub mod refund_losers_batch;
+pub mod slash_defaulter;
 pub mod finalize_no_winner;
 pub mod reclaim_unsold;
-pub mod close_settled;
+pub mod expire_unresolved;
 
+pub use initialize_config::*;
+pub use update_config::*;
 pub use create_auction::*;
+pub use create_compressed_auction::*;
+pub use create_core_auction::*;
 pub use cancel_auction::*;
 pub use lock_bid_funds::*;
+pub use increase_bid_collateral::*;
+pub use withdraw_bid::*;
+pub use close_bid_withdrawal::*;
 pub use settle_auction::*;
 pub use refund_loser::*;
+pub use refund_losers_batch::*;
+pub use slash_defaulter::*;
 pub use finalize_no_winner::*;
 pub use reclaim_unsold::*;
-pub use close_settled::*;
+pub use expire_unresolved::*;
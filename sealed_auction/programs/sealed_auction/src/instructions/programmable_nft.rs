@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata::instructions::TransferV1CpiBuilder;
+use anchor_spl::metadata::mpl_token_metadata::types::TokenStandard;
+use anchor_spl::metadata::{self, MetadataAccount};
+use crate::errors::AuctionError;
+
+/// Extra accounts for moving Metaplex programmable NFTs (pNFTs), whose token
+/// accounts are frozen and can only be moved by Token Metadata's Transfer.
+/// Omit all of them for plain SPL / Token-2022 NFTs.
+#[derive(Accounts)]
+pub struct ProgrammableNftAccounts<'info> {
+    /// CHECK: Metadata PDA of the NFT mint, verified in `is_programmable`
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Master edition PDA, verified by Token Metadata
+    pub edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the source token account, verified by Token Metadata
+    #[account(mut)]
+    pub owner_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the destination token account, verified by Token Metadata
+    #[account(mut)]
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the pNFT (if any), verified by Token Metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token Auth Rules program, verified by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    pub token_metadata_program: Option<Program<'info, metadata::Metadata>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+impl<'info> ProgrammableNftAccounts<'info> {
+    /// True when `mint` has a Token Metadata account marking it programmable
+    pub fn is_programmable(&self, mint: &Pubkey) -> Result<bool> {
+        let Some(nft_metadata) = &self.metadata else {
+            return Ok(false);
+        };
+
+        let (expected, _) = Pubkey::find_program_address(
+            &[b"metadata", metadata::ID.as_ref(), mint.as_ref()],
+            &metadata::ID,
+        );
+        require_keys_eq!(nft_metadata.key(), expected, AuctionError::InvalidProgrammableNftAccounts);
+
+        let info = nft_metadata.to_account_info();
+        if info.owner != &metadata::ID || info.data_is_empty() {
+            return Ok(false);
+        }
+        let nft_metadata = MetadataAccount::try_deserialize(&mut &info.data.borrow()[..])?;
+
+        Ok(matches!(
+            nft_metadata.token_standard,
+            Some(TokenStandard::ProgrammableNonFungible | TokenStandard::ProgrammableNonFungibleEdition)
+        ))
+    }
+
+    /// Move the pNFT with Token Metadata's TransferV1 (thaw, transfer, re-freeze).
+    /// `signer_seeds` is empty when `token_owner` signs the transaction itself.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer(
+        &self,
+        token: AccountInfo<'info>,
+        token_owner: AccountInfo<'info>,
+        destination_token: AccountInfo<'info>,
+        destination_owner: AccountInfo<'info>,
+        mint: AccountInfo<'info>,
+        payer: AccountInfo<'info>,
+        spl_token_program: AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let (
+            Some(nft_metadata),
+            Some(edition),
+            Some(owner_token_record),
+            Some(destination_token_record),
+            Some(sysvar_instructions),
+            Some(token_metadata_program),
+            Some(associated_token_program),
+            Some(system_program),
+        ) = (
+            &self.metadata,
+            &self.edition,
+            &self.owner_token_record,
+            &self.destination_token_record,
+            &self.sysvar_instructions,
+            &self.token_metadata_program,
+            &self.associated_token_program,
+            &self.system_program,
+        ) else {
+            return err!(AuctionError::InvalidProgrammableNftAccounts);
+        };
+
+        let program = token_metadata_program.to_account_info();
+        let nft_metadata = nft_metadata.to_account_info();
+        let edition = edition.to_account_info();
+        let owner_token_record = owner_token_record.to_account_info();
+        let destination_token_record = destination_token_record.to_account_info();
+        let sysvar_instructions = sysvar_instructions.to_account_info();
+        let associated_token_program = associated_token_program.to_account_info();
+        let system_program = system_program.to_account_info();
+        let authorization_rules = self.authorization_rules.as_ref().map(|a| a.to_account_info());
+        let authorization_rules_program = self
+            .authorization_rules_program
+            .as_ref()
+            .map(|a| a.to_account_info());
+
+        TransferV1CpiBuilder::new(&program)
+            .token(&token)
+            .token_owner(&token_owner)
+            .destination_token(&destination_token)
+            .destination_owner(&destination_owner)
+            .mint(&mint)
+            .metadata(&nft_metadata)
+            .edition(Some(&edition))
+            .token_record(Some(&owner_token_record))
+            .destination_token_record(Some(&destination_token_record))
+            .authority(&token_owner)
+            .payer(&payer)
+            .system_program(&system_program)
+            .sysvar_instructions(&sysvar_instructions)
+            .spl_token_program(&spl_token_program)
+            .spl_ata_program(&associated_token_program)
+            .authorization_rules(authorization_rules.as_ref())
+            .authorization_rules_program(authorization_rules_program.as_ref())
+            .amount(1)
+            .invoke_signed(signer_seeds)?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
+use crate::errors::AuctionError;
 
 
 #[derive(Accounts)]
@@ -20,5 +22,38 @@ pub struct RefundLoser<'info> {
 
     pub auction: Account<'info, Auction>,
 
+    /// Revealed result, checked for defaulters. Omit only for expired auctions.
+    #[account(
+        seeds = [AUCTION_RESULT_SEED, auction.key().as_ref()],
+        bump = auction_result.bump,
+        seeds::program = ARCIUM_PROGRAM_ID,
+    )]
+    pub auction_result: Option<Account<'info, AuctionResult>>,
+
+    // --- SPL payment auctions only ---
+
+    #[account(
+        constraint = auction.payment_mint == Some(payment_mint.key()) @ AuctionError::InvalidPaymentMint,
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        seeds = [b"bid_vault", bid_escrow.key().as_ref()],
+        bump,
+        token::authority = bid_escrow,
+        token::token_program = payment_token_program,
+    )]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        constraint = bidder_payment_account.owner == bidder.key(),
+        constraint = Some(bidder_payment_account.mint) == auction.payment_mint @ AuctionError::InvalidPaymentMint,
+    )]
+    pub bidder_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::state::*;
+use crate::errors::AuctionError;
+
+/// Refund many losing bidders in one call. Anyone can crank it; each bidder's
+/// (bidder, bid_escrow) pair, or (bidder, bid_escrow, bid_vault, bidder token
+/// account) group for SPL auctions, goes in remaining accounts.
+#[derive(Accounts)]
+pub struct RefundLosersBatch<'info> {
+    pub auction: Account<'info, Auction>,
+
+    /// Revealed result, checked for winners and defaulters. Omit only for expired auctions.
+    #[account(
+        seeds = [AUCTION_RESULT_SEED, auction.key().as_ref()],
+        bump = auction_result.bump,
+        seeds::program = ARCIUM_PROGRAM_ID,
+    )]
+    pub auction_result: Option<Account<'info, AuctionResult>>,
+
+    // --- SPL payment auctions only ---
+
+    #[account(
+        constraint = auction.payment_mint == Some(payment_mint.key()) @ AuctionError::InvalidPaymentMint,
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::*;
+use crate::errors::AuctionError;
+use crate::instructions::programmable_nft::*;
+use crate::instructions::compressed_nft::*;
+use crate::instructions::core_asset::*;
+
+/// The accounts every instruction releasing an escrowed asset carries.
+/// Only the ones matching the auction's asset kind have to be present.
+pub struct EscrowedAsset<'a, 'info> {
+    pub nft_mint: Option<&'a InterfaceAccount<'info, Mint>>,
+    pub nft_escrow_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<&'a Interface<'info, TokenInterface>>,
+    pub bundle: Option<&'a Account<'info, Bundle>>,
+    pub pnft: &'a ProgrammableNftAccounts<'info>,
+    pub cnft: &'a CompressedNftAccounts<'info>,
+    pub core: &'a CoreAssetAccounts<'info>,
+    /// Merkle root the compressed NFT's proof was fetched against
+    pub cnft_root: Option<[u8; 32]>,
+    /// Pays for any accounts Core or Token Metadata create on the way
+    pub payer: AccountInfo<'info>,
+}
+
+/// Move the auction's asset out of escrow to `to`, signed by the auction PDA.
+/// `to_token_account` is `to`'s token account for SPL, Token-2022 and pNFT
+/// listings. `remaining_accounts` holds the merkle proof of a compressed NFT or
+/// the (mint, escrow, destination token account) triples of a bundle.
+/// `amount` is the number of tokens to send; `None` empties the escrow.
+pub fn release_asset<'info>(
+    auction: &Account<'info, Auction>,
+    to: AccountInfo<'info>,
+    to_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    asset: EscrowedAsset<'_, 'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    amount: Option<u64>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if auction.asset_kind == AssetKind::Compressed {
+        let Some(compressed) = auction.compressed else {
+            return err!(AuctionError::InvalidCompressedNftAccounts);
+        };
+        let Some(root) = asset.cnft_root else {
+            return err!(AuctionError::MissingCompressedNftRoot);
+        };
+        return asset.cnft.transfer(
+            &compressed,
+            root,
+            auction.to_account_info(),
+            to,
+            remaining_accounts,
+            signer_seeds,
+        );
+    }
+
+    if auction.asset_kind == AssetKind::Core {
+        return asset.core.transfer(
+            &auction.nft_mint,
+            auction.to_account_info(),
+            to,
+            asset.payer,
+            signer_seeds,
+        );
+    }
+
+    let (Some(nft_mint), Some(nft_escrow_account), Some(to_token_account), Some(token_program)) = (
+        asset.nft_mint,
+        asset.nft_escrow_account,
+        to_token_account,
+        asset.token_program,
+    ) else {
+        return err!(AuctionError::MissingNftAccounts);
+    };
+
+    if auction.asset_kind == AssetKind::ProgrammableNft {
+        asset.pnft.transfer(
+            nft_escrow_account.to_account_info(),
+            auction.to_account_info(),
+            to_token_account.to_account_info(),
+            to.clone(),
+            nft_mint.to_account_info(),
+            asset.payer,
+            token_program.to_account_info(),
+            signer_seeds,
+        )?;
+    } else {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: nft_escrow_account.to_account_info(),
+                    mint: nft_mint.to_account_info(),
+                    to: to_token_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount.unwrap_or(nft_escrow_account.amount),
+            nft_mint.decimals,
+        )?;
+    }
+
+    // The rest of a bundle moves with it
+    if auction.bundle_items > 0 {
+        let Some(bundle) = asset.bundle else {
+            return err!(AuctionError::InvalidBundle);
+        };
+        crate::instructions::bundle::release_bundle(
+            bundle,
+            remaining_accounts,
+            &to.key(),
+            auction.to_account_info(),
+            token_program.to_account_info(),
+            signer_seeds,
+        )?;
+    }
+
+    Ok(())
+}
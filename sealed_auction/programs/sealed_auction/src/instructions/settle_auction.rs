@@ -1,10 +1,20 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint, Transfer};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::metadata::{self, MetadataAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
+use crate::instructions::programmable_nft::*;
+use crate::instructions::compressed_nft::*;
+use crate::instructions::core_asset::*;
+use crate::errors::AuctionError;
 
 
 #[derive(Accounts)]
 pub struct SettleAuction<'info> {
+    /// Anyone can crank settlement; pays rent for pNFT token records
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     /// CHECK: Safe because we only transfer lamports to it
     #[account(mut)]
     pub seller: UncheckedAccount<'info>,
@@ -15,8 +25,9 @@ pub struct SettleAuction<'info> {
 
     #[account(
         mut,
+        seeds = [b"auction", auction.nft_mint.as_ref(), auction.listing_id.to_le_bytes().as_ref()],
+        bump = auction.bump,
         has_one = seller,
-        has_one = nft_mint,
     )]
     pub auction: Account<'info, Auction>,
 
@@ -39,33 +50,170 @@ pub struct SettleAuction<'info> {
     )]
     pub winner_bid_escrow: Account<'info, BidEscrow>,
 
+    /// CHECK: Safe - must match the treasury snapshotted in the auction.
+    /// Only needed when the auction charges a protocol fee.
+    #[account(mut, address = auction.treasury)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
     #[account(
         mut,
         token::mint = nft_mint,
-        token::authority = auction
+        token::authority = auction,
+        token::token_program = token_program,
     )]
-    pub nft_escrow_account: Account<'info, TokenAccount>,
+    pub nft_escrow_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         mut,
         constraint = winner_nft_account.owner == winner.key(),
-        constraint = winner_nft_account.mint == nft_mint.key(),
+        constraint = winner_nft_account.mint == auction.nft_mint,
+    )]
+    pub winner_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Receives the units nobody won when the last multi-unit winner claims
+    #[account(
+        mut,
+        constraint = seller_nft_account.owner == seller.key(),
+        constraint = seller_nft_account.mint == auction.nft_mint,
+    )]
+    pub seller_nft_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: Metaplex metadata PDA of nft_mint. May be empty for mints without
+    /// metadata (and always is for compressed NFTs); when it exists, creator
+    /// royalties are paid from the clearing price.
+    #[account(
+        seeds = [b"metadata", metadata::ID.as_ref(), auction.nft_mint.as_ref()],
+        bump,
+        seeds::program = metadata::ID,
     )]
-    pub winner_nft_account: Account<'info, TokenAccount>,
+    pub nft_metadata: UncheckedAccount<'info>,
 
-    pub nft_mint: Account<'info, Mint>,
+    // --- SPL payment auctions only ---
+
+    #[account(
+        constraint = auction.payment_mint == Some(payment_mint.key()) @ AuctionError::InvalidPaymentMint,
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Winner's locked payment tokens
+    #[account(
+        mut,
+        seeds = [b"bid_vault", winner_bid_escrow.key().as_ref()],
+        bump,
+        token::authority = winner_bid_escrow,
+        token::token_program = payment_token_program,
+    )]
+    pub winner_escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        constraint = seller_payment_account.owner == seller.key(),
+        constraint = Some(seller_payment_account.mint) == auction.payment_mint @ AuctionError::InvalidPaymentMint,
+    )]
+    pub seller_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(
+        mut,
+        constraint = winner_payment_account.owner == winner.key(),
+        constraint = Some(winner_payment_account.mint) == auction.payment_mint @ AuctionError::InvalidPaymentMint,
+    )]
+    pub winner_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Only needed when the auction charges a protocol fee
+    #[account(
+        mut,
+        constraint = treasury_payment_account.owner == auction.treasury,
+        constraint = Some(treasury_payment_account.mint) == auction.payment_mint @ AuctionError::InvalidPaymentMint,
+    )]
+    pub treasury_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Token program owning the payment mint (may differ from the NFT's)
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Token program owning the NFT mint (Token or Token-2022)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
+
+    /// Only for bundles; the extra NFTs go in remaining accounts
+    #[account(seeds = [b"bundle", auction.key().as_ref()], bump = bundle.bump)]
+    pub bundle: Option<Box<Account<'info, Bundle>>>,
+
+    /// Only needed for programmable NFTs
+    pub pnft: ProgrammableNftAccounts<'info>,
+
+    /// Only needed for compressed NFTs; the merkle proof goes in remaining accounts
+    pub cnft: CompressedNftAccounts<'info>,
+
+    /// Only needed for Metaplex Core assets
+    pub core: CoreAssetAccounts<'info>,
 }
 
 impl<'info> SettleAuction<'info> {
-    pub fn into_transfer_to_winner(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.nft_escrow_account.to_account_info(),
-            to: self.winner_nft_account.to_account_info(),
-            authority: self.auction.to_account_info(),
-        };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    /// Royalty owed to each verified creator out of `amount`, in metadata order.
+    /// Unverified creators are skipped and their share stays with the seller.
+    pub fn creator_royalties(&self, amount: u64) -> Result<Vec<(Pubkey, u64)>> {
+        let info = self.nft_metadata.to_account_info();
+        if info.owner != &metadata::ID || info.data_is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let nft_metadata = MetadataAccount::try_deserialize(&mut &info.data.borrow()[..])?;
+        let royalty = amount as u128 * nft_metadata.seller_fee_basis_points as u128
+            / BPS_DENOMINATOR as u128;
+
+        Ok(nft_metadata
+            .creators
+            .iter()
+            .flatten()
+            .filter(|creator| creator.verified)
+            .map(|creator| (creator.address, (royalty * creator.share as u128 / 100) as u64))
+            .collect())
+    }
+
+    /// Checks each creator account against its creator and zeroes the royalties
+    /// it cannot receive, so they stay with the seller instead of failing the
+    /// settlement: lamports that would leave the wallet below rent exemption,
+    /// or tokens for a creator ATA that is missing, frozen or no longer theirs.
+    pub fn payable_royalties(
+        &self,
+        royalties: Vec<(Pubkey, u64)>,
+        creator_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Vec<(Pubkey, u64)>> {
+        let rent = Rent::get()?;
+
+        royalties
+            .into_iter()
+            .zip(creator_accounts)
+            .map(|((creator, amount), creator_account)| {
+                let payable = if let Some(payment_mint) = self.auction.payment_mint {
+                    let Some(payment_token_program) = &self.payment_token_program else {
+                        return err!(AuctionError::MissingPaymentAccounts);
+                    };
+                    let creator_ata = get_associated_token_address_with_program_id(
+                        &creator,
+                        &payment_mint,
+                        &payment_token_program.key(),
+                    );
+                    require_keys_eq!(creator_account.key(), creator_ata, AuctionError::InvalidCreatorAccounts);
+
+                    InterfaceAccount::<TokenAccount>::try_from(creator_account)
+                        .map(|account| account.owner == creator && !account.is_frozen())
+                        .unwrap_or(false)
+                } else {
+                    require_keys_eq!(creator_account.key(), creator, AuctionError::InvalidCreatorAccounts);
+
+                    !creator_account.executable
+                        && rent.is_exempt(
+                            creator_account.lamports().saturating_add(amount),
+                            creator_account.data_len(),
+                        )
+                };
+
+                Ok((creator, if payable { amount } else { 0 }))
+            })
+            .collect()
     }
 }
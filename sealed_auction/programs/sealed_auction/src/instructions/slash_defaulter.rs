@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::AuctionError;
+
+/// Pays part of a revealed defaulter's collateral to the seller. Anyone can crank it.
+#[derive(Accounts)]
+pub struct SlashDefaulter<'info> {
+    /// CHECK: Safe because we only transfer lamports to it
+    #[account(mut, address = auction.seller)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: The defaulting bidder, checked against the AuctionResult
+    pub bidder: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid_escrow.bump,
+        constraint = bid_escrow.bidder == bidder.key(),
+        constraint = bid_escrow.auction == auction.key(),
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+
+    #[account(
+        seeds = [b"auction", auction.nft_mint.as_ref(), auction.listing_id.to_le_bytes().as_ref()],
+        bump = auction.bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// The revealed result listing the defaulters
+    #[account(
+        seeds = [AUCTION_RESULT_SEED, auction.key().as_ref()],
+        bump = auction_result.bump,
+        seeds::program = ARCIUM_PROGRAM_ID,
+    )]
+    pub auction_result: Account<'info, AuctionResult>,
+
+    // --- SPL payment auctions only ---
+
+    #[account(
+        constraint = auction.payment_mint == Some(payment_mint.key()) @ AuctionError::InvalidPaymentMint,
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        seeds = [b"bid_vault", bid_escrow.key().as_ref()],
+        bump,
+        token::authority = bid_escrow,
+        token::token_program = payment_token_program,
+    )]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        constraint = seller_payment_account.owner == seller.key(),
+        constraint = Some(seller_payment_account.mint) == auction.payment_mint @ AuctionError::InvalidPaymentMint,
+    )]
+    pub seller_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::AuctionError;
+
+/// Admin-only. Auctions snapshot the fee at creation, so changes only affect future auctions.
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::AuctionError;
+
+
+#[derive(Accounts)]
+pub struct WithdrawBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid_escrow.bump,
+        constraint = bid_escrow.bidder == bidder.key(),
+        constraint = bid_escrow.auction == auction.key(),
+        close = bidder,
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+
+    /// Receipt arcium_program's retract_bid consumes, in withdrawal order
+    #[account(
+        init,
+        payer = bidder,
+        seeds = [b"bid_withdrawal", auction.key().as_ref(), auction.withdrawal_count.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + 32 + 32 + 8 + 1 + 8
+    )]
+    pub bid_withdrawal: Account<'info, BidWithdrawal>,
+
+    // --- SPL payment auctions only ---
+
+    #[account(
+        constraint = auction.payment_mint == Some(payment_mint.key()) @ AuctionError::InvalidPaymentMint,
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        seeds = [b"bid_vault", bid_escrow.key().as_ref()],
+        bump,
+        token::authority = bid_escrow,
+        token::token_program = payment_token_program,
+    )]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        constraint = bidder_payment_account.owner == bidder.key(),
+        constraint = Some(bidder_payment_account.mint) == auction.payment_mint @ AuctionError::InvalidPaymentMint,
+    )]
+    pub bidder_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,5 +1,8 @@
+// `into_*` CPI helpers on account contexts borrow `self`, following Anchor convention
+#![allow(clippy::wrong_self_convention)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self};
+use anchor_spl::token_interface;
 use anchor_lang::system_program;
 
 mod state;
@@ -9,6 +12,7 @@ mod instructions;
 use state::*;
 use errors::*;
 use instructions::*;
+use instructions::release_asset::*;
 
 
 declare_id!("2rTWXsHTnJdSKxJjdG1wDWdQYFFD3b6RfHbqi3VsR2dt");
@@ -17,28 +21,275 @@ declare_id!("2rTWXsHTnJdSKxJjdG1wDWdQYFFD3b6RfHbqi3VsR2dt");
 pub mod sealed_auction {
     use super::*;
 
-    pub fn create_auction(
-        ctx: Context<CreateAuction>,
+    /// Create the global Config. Only the program's upgrade authority can call this, once.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        admin: Pubkey,
+        fee_bps: u16,
+        treasury: Pubkey,
+        slash_bps: u16,
+    ) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, AuctionError::InvalidFeeBps);
+        require!(slash_bps as u64 <= BPS_DENOMINATOR, AuctionError::InvalidSlashBps);
+
+        let config = &mut ctx.accounts.config;
+        config.admin = admin;
+        config.fee_bps = fee_bps;
+        config.treasury = treasury;
+        config.slash_bps = slash_bps;
+        config.bump = ctx.bumps.config;
+
+        Ok(())
+    }
+
+    /// Update protocol settings. Existing auctions keep the fee, treasury
+    /// and slash share they were created with.
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        new_admin: Option<Pubkey>,
+        fee_bps: u16,
+        treasury: Pubkey,
+        slash_bps: u16,
+    ) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, AuctionError::InvalidFeeBps);
+        require!(slash_bps as u64 <= BPS_DENOMINATOR, AuctionError::InvalidSlashBps);
+
+        let config = &mut ctx.accounts.config;
+        if let Some(new_admin) = new_admin {
+            config.admin = new_admin;
+        }
+        config.fee_bps = fee_bps;
+        config.treasury = treasury;
+        config.slash_bps = slash_bps;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateAuction<'info>>,
+        listing_id: u64,
         min_price: u64,
+        start_time: i64,
         end_time: i64,
+        auction_type: AuctionType,
+        hidden_reserve: bool,
+        resolution_grace_period: i64,
+        soft_close: Option<SoftClose>,
+        lot: Option<Lot>,
     ) -> Result<()> {
         let auction = &mut ctx.accounts.auction;
-        let now = Clock::get()?.unix_timestamp;
 
-        require!(end_time > now, AuctionError::InvalidEndTime);
-        require!(min_price > 0, AuctionError::InvalidMinPrice);
+        validate_listing(min_price, start_time, end_time, hidden_reserve, resolution_grace_period, &soft_close)?;
 
         auction.seller = ctx.accounts.seller.key();
         auction.nft_mint = ctx.accounts.nft_mint.key();
+        auction.listing_id = listing_id;
+        auction.min_price = min_price;
+        auction.start_time = start_time;
+        auction.end_time = end_time;
+        auction.settled = false;
+        auction.bump = ctx.bumps.auction;
+        auction.auction_type = auction_type;
+        auction.payment_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+        auction.hidden_reserve = hidden_reserve;
+        auction.resolution_grace_period = resolution_grace_period;
+        auction.expired = false;
+        auction.withdrawal_count = 0;
+        auction.soft_close = soft_close;
+        auction.fee_bps = ctx.accounts.config.fee_bps;
+        auction.treasury = ctx.accounts.config.treasury;
+        auction.slash_bps = ctx.accounts.config.slash_bps;
+        auction.asset_kind = if ctx.accounts.pnft.is_programmable(&ctx.accounts.nft_mint.key())? {
+            AssetKind::ProgrammableNft
+        } else {
+            AssetKind::Spl
+        };
+        auction.compressed = None;
+
+        // A plain NFT is a single unit of one token
+        let lot = lot.unwrap_or(Lot { units: 1, unit_amount: 1 });
+        require!(
+            lot.units >= 1 && lot.units as usize <= MAX_UNITS && lot.unit_amount > 0,
+            AuctionError::InvalidLot
+        );
+        if lot.units > 1 || lot.unit_amount > 1 {
+            require!(auction.asset_kind == AssetKind::Spl, AuctionError::InvalidLot);
+        }
+        auction.units = lot.units;
+        auction.unit_amount = lot.unit_amount;
+        auction.units_claimed = 0;
+
+        // Move NFT (or every unit of the lot) to escrow
+        if auction.asset_kind == AssetKind::ProgrammableNft {
+            ctx.accounts.pnft.transfer(
+                ctx.accounts.seller_nft_account.to_account_info(),
+                ctx.accounts.seller.to_account_info(),
+                ctx.accounts.nft_escrow_account.to_account_info(),
+                ctx.accounts.auction.to_account_info(),
+                ctx.accounts.nft_mint.to_account_info(),
+                ctx.accounts.seller.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                &[],
+            )?;
+        } else {
+            let amount = (lot.units as u64)
+                .checked_mul(lot.unit_amount)
+                .ok_or(AuctionError::InvalidLot)?;
+            token_interface::transfer_checked(
+                ctx.accounts.into_transfer_to_escrow(),
+                amount,
+                ctx.accounts.nft_mint.decimals,
+            )?;
+        }
+
+        // Bundles: the rest of the set comes as (mint, seller token account, escrow)
+        // triples in remaining accounts and is escrowed alongside nft_mint
+        if let Some(bundle) = &mut ctx.accounts.bundle {
+            require!(
+                !ctx.remaining_accounts.is_empty()
+                    && ctx.accounts.auction.asset_kind == AssetKind::Spl
+                    && ctx.accounts.auction.units == 1
+                    && ctx.accounts.auction.unit_amount == 1,
+                AuctionError::InvalidBundle
+            );
+            let nft_mint = &ctx.accounts.nft_mint;
+            require!(nft_mint.decimals == 0 && nft_mint.supply == 1, AuctionError::BundleItemNotNft);
+            let items = instructions::bundle::escrow_bundle(
+                ctx.remaining_accounts,
+                &ctx.accounts.nft_mint.key(),
+                &ctx.accounts.auction.key(),
+                ctx.accounts.seller.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+            )?;
+
+            bundle.auction = ctx.accounts.auction.key();
+            bundle.bump = ctx.bumps.bundle.unwrap();
+            ctx.accounts.auction.bundle_items = items.len() as u8;
+            bundle.items = items;
+        } else {
+            require!(ctx.remaining_accounts.is_empty(), AuctionError::InvalidBundle);
+            ctx.accounts.auction.bundle_items = 0;
+        }
+
+        Ok(())
+    }
+
+    /// List a Bubblegum compressed NFT. `compressed` and `root` come from the DAS
+    /// API (asset proof / asset); the proof itself is passed as remaining accounts.
+    /// The leaf must not be delegated.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_compressed_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateCompressedAuction<'info>>,
+        listing_id: u64,
+        compressed: CompressedNft,
+        root: [u8; 32],
+        min_price: u64,
+        start_time: i64,
+        end_time: i64,
+        auction_type: AuctionType,
+        hidden_reserve: bool,
+        resolution_grace_period: i64,
+        soft_close: Option<SoftClose>,
+    ) -> Result<()> {
+        validate_listing(min_price, start_time, end_time, hidden_reserve, resolution_grace_period, &soft_close)?;
+        // The asset id is derived from the leaf, so the auction PDA can't be keyed by another asset
+        require_keys_eq!(
+            compressed.asset_id,
+            instructions::compressed_nft::CompressedNftAccounts::asset_id(&compressed.merkle_tree, compressed.nonce),
+            AuctionError::InvalidCompressedNftAccounts
+        );
+
+        let auction = &mut ctx.accounts.auction;
+        auction.seller = ctx.accounts.seller.key();
+        auction.nft_mint = compressed.asset_id;
+        auction.listing_id = listing_id;
+        auction.min_price = min_price;
+        auction.start_time = start_time;
+        auction.end_time = end_time;
+        auction.settled = false;
+        auction.bump = ctx.bumps.auction;
+        auction.auction_type = auction_type;
+        auction.payment_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+        auction.hidden_reserve = hidden_reserve;
+        auction.resolution_grace_period = resolution_grace_period;
+        auction.expired = false;
+        auction.withdrawal_count = 0;
+        auction.soft_close = soft_close;
+        auction.fee_bps = ctx.accounts.config.fee_bps;
+        auction.treasury = ctx.accounts.config.treasury;
+        auction.slash_bps = ctx.accounts.config.slash_bps;
+        auction.asset_kind = AssetKind::Compressed;
+        auction.compressed = Some(compressed);
+        auction.units = 1;
+        auction.unit_amount = 1;
+        auction.units_claimed = 0;
+        auction.bundle_items = 0;
+
+        // Hand the leaf to the auction PDA; Bubblegum rejects a wrong proof or leaf data
+        ctx.accounts.cnft.transfer(
+            &compressed,
+            root,
+            ctx.accounts.seller.to_account_info(),
+            ctx.accounts.auction.to_account_info(),
+            ctx.remaining_accounts,
+            &[],
+        )?;
+
+        Ok(())
+    }
+
+    /// List a Metaplex Core asset. The seller signs Core's TransferV1 to the auction PDA.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_core_auction(
+        ctx: Context<CreateCoreAuction>,
+        listing_id: u64,
+        min_price: u64,
+        start_time: i64,
+        end_time: i64,
+        auction_type: AuctionType,
+        hidden_reserve: bool,
+        resolution_grace_period: i64,
+        soft_close: Option<SoftClose>,
+    ) -> Result<()> {
+        validate_listing(min_price, start_time, end_time, hidden_reserve, resolution_grace_period, &soft_close)?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.seller = ctx.accounts.seller.key();
+        auction.nft_mint = ctx.accounts.asset.key();
+        auction.listing_id = listing_id;
         auction.min_price = min_price;
+        auction.start_time = start_time;
         auction.end_time = end_time;
         auction.settled = false;
         auction.bump = ctx.bumps.auction;
+        auction.auction_type = auction_type;
+        auction.payment_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+        auction.hidden_reserve = hidden_reserve;
+        auction.resolution_grace_period = resolution_grace_period;
+        auction.expired = false;
+        auction.withdrawal_count = 0;
+        auction.soft_close = soft_close;
+        auction.fee_bps = ctx.accounts.config.fee_bps;
+        auction.treasury = ctx.accounts.config.treasury;
+        auction.slash_bps = ctx.accounts.config.slash_bps;
+        auction.asset_kind = AssetKind::Core;
+        auction.compressed = None;
+        auction.units = 1;
+        auction.unit_amount = 1;
+        auction.units_claimed = 0;
+        auction.bundle_items = 0;
 
-        // Move NFT to escrow
-        token::transfer(
-            ctx.accounts.into_transfer_to_escrow(),
-            1,
+        // Core rejects the transfer unless the seller owns the asset
+        instructions::core_asset::transfer_core_asset(
+            ctx.accounts.asset.to_account_info(),
+            ctx.accounts.collection.as_ref().map(|c| c.to_account_info()),
+            ctx.accounts.seller.to_account_info(),
+            ctx.accounts.auction.to_account_info(),
+            ctx.accounts.seller.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.core_program.to_account_info(),
+            &[],
         )?;
 
         Ok(())
@@ -51,8 +302,20 @@ pub mod sealed_auction {
         let auction = &mut ctx.accounts.auction;
         let now = Clock::get()?.unix_timestamp;
 
+        require!(now >= auction.start_time, AuctionError::AuctionNotStarted);
         require!(now < auction.end_time, AuctionError::AuctionEnded);
 
+        // Soft close: a late lock extends the auction, up to the cap.
+        // reveal_winner, submit_bid and settle_auction all read the updated end_time.
+        if let Some(soft_close) = auction.soft_close {
+            if auction.end_time - now <= soft_close.window {
+                auction.end_time = auction
+                    .end_time
+                    .saturating_add(soft_close.extension)
+                    .min(soft_close.max_end_time);
+            }
+        }
+
         auction.bid_count += 1;
         let escrow = &mut ctx.accounts.bid_escrow;
         escrow.auction = auction.key();
@@ -60,26 +323,217 @@ pub mod sealed_auction {
         escrow.max_locked_amount = max_locked_amount;
         escrow.withdrawn = false;
         escrow.bump = ctx.bumps.bid_escrow;
+        escrow.slashed = false;
+        escrow.locked_slot = Clock::get()?.slot;
+
+        if auction.payment_mint.is_some() {
+            // Transfer tokens into the vault owned by the bid_escrow PDA
+            let (Some(payment_mint), Some(bidder_payment_account), Some(escrow_vault), Some(token_program)) = (
+                &ctx.accounts.payment_mint,
+                &ctx.accounts.bidder_payment_account,
+                &mut ctx.accounts.escrow_vault,
+                &ctx.accounts.payment_token_program,
+            ) else {
+                return err!(AuctionError::MissingPaymentAccounts);
+            };
+
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: bidder_payment_account.to_account_info(),
+                        mint: payment_mint.to_account_info(),
+                        to: escrow_vault.to_account_info(),
+                        authority: ctx.accounts.bidder.to_account_info(),
+                    },
+                ),
+                max_locked_amount,
+                payment_mint.decimals,
+            )?;
+
+            // Token-2022 transfer fees are withheld from the vault, so only
+            // what actually arrived counts as collateral
+            escrow_vault.reload()?;
+            escrow.max_locked_amount = escrow_vault.amount;
+        } else {
+            // Transfer SOL into PDA via System Program
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bidder.to_account_info(),
+                        to: ctx.accounts.bid_escrow.to_account_info(),
+                    },
+                ),
+                max_locked_amount,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Add collateral to an existing BidEscrow before the auction ends.
+    /// The bidder must submit a new encrypted bid to arcium_program for the
+    /// higher collateral to cover a higher bid.
+    pub fn increase_bid_collateral(
+        ctx: Context<IncreaseBidCollateral>,
+        amount: u64,
+    ) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(now < auction.end_time, AuctionError::AuctionEnded);
+        require!(amount > 0, AuctionError::InvalidAmount);
+        require!(!ctx.accounts.bid_escrow.withdrawn, AuctionError::AlreadyWithdrawn);
+
+        let escrow = &mut ctx.accounts.bid_escrow;
+
+        if auction.payment_mint.is_some() {
+            let (Some(payment_mint), Some(bidder_payment_account), Some(escrow_vault), Some(token_program)) = (
+                &ctx.accounts.payment_mint,
+                &ctx.accounts.bidder_payment_account,
+                &mut ctx.accounts.escrow_vault,
+                &ctx.accounts.payment_token_program,
+            ) else {
+                return err!(AuctionError::MissingPaymentAccounts);
+            };
+
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: bidder_payment_account.to_account_info(),
+                        mint: payment_mint.to_account_info(),
+                        to: escrow_vault.to_account_info(),
+                        authority: ctx.accounts.bidder.to_account_info(),
+                    },
+                ),
+                amount,
+                payment_mint.decimals,
+            )?;
+
+            // As in lock_bid_funds, only what actually reached the vault counts
+            escrow_vault.reload()?;
+            escrow.max_locked_amount = escrow_vault.amount;
+        } else {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bidder.to_account_info(),
+                        to: escrow.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+
+            escrow.max_locked_amount = escrow
+                .max_locked_amount
+                .checked_add(amount)
+                .ok_or(AuctionError::InvalidAmount)?;
+        }
 
-        // Transfer SOL into PDA via System Program
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.bidder.to_account_info(),
-                    to: ctx.accounts.bid_escrow.to_account_info(),
+        Ok(())
+    }
+
+    /// Pull out of a live auction: returns all collateral, closes the BidEscrow
+    /// and records a BidWithdrawal receipt. arcium_program's permissionless
+    /// retract_bid must process the receipt before further bids or the reveal.
+    pub fn withdraw_bid(ctx: Context<WithdrawBid>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(now < ctx.accounts.auction.end_time, AuctionError::AuctionEnded);
+        require!(!ctx.accounts.bid_escrow.withdrawn, AuctionError::AlreadyWithdrawn);
+        // arcium_program only retracts bids from the single-unit state
+        require!(ctx.accounts.auction.units == 1, AuctionError::WithdrawalNotSupported);
+        // Any new escrow then gets a later locked_slot than this one, which
+        // keeps its bid apart from the one being retracted
+        require!(
+            Clock::get()?.slot > ctx.accounts.bid_escrow.locked_slot,
+            AuctionError::WithdrawalTooEarly
+        );
+        // bid_count + withdrawal_count is every escrow ever locked, each holding at
+        // most one ranked slot; beyond RANKED_BIDS a retraction could promote a bid
+        // ranked below one that was already dropped
+        require!(
+            ctx.accounts.auction.bid_count + ctx.accounts.auction.withdrawal_count <= RANKED_BIDS,
+            AuctionError::WithdrawalsClosed
+        );
+
+        if ctx.accounts.auction.payment_mint.is_some() {
+            // Return every locked token, then close the vault
+            let (Some(payment_mint), Some(escrow_vault), Some(bidder_payment_account), Some(token_program)) = (
+                &ctx.accounts.payment_mint,
+                &ctx.accounts.escrow_vault,
+                &ctx.accounts.bidder_payment_account,
+                &ctx.accounts.payment_token_program,
+            ) else {
+                return err!(AuctionError::MissingPaymentAccounts);
+            };
+
+            let auction_key = ctx.accounts.auction.key();
+            let bidder_key = ctx.accounts.bidder.key();
+            let seeds = &[
+                b"bid_escrow",
+                auction_key.as_ref(),
+                bidder_key.as_ref(),
+                &[ctx.accounts.bid_escrow.bump],
+            ];
+            let signer_seeds = &[&seeds[..]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: escrow_vault.to_account_info(),
+                        mint: payment_mint.to_account_info(),
+                        to: bidder_payment_account.to_account_info(),
+                        authority: ctx.accounts.bid_escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                escrow_vault.amount,
+                payment_mint.decimals,
+            )?;
+
+            token_interface::close_account(CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_interface::CloseAccount {
+                    account: escrow_vault.to_account_info(),
+                    destination: ctx.accounts.bidder.to_account_info(),
+                    authority: ctx.accounts.bid_escrow.to_account_info(),
                 },
-            ),
-            max_locked_amount,
-        )?;
+                signer_seeds,
+            ))?;
+        }
+
+        let auction = &mut ctx.accounts.auction;
+        let receipt = &mut ctx.accounts.bid_withdrawal;
+        receipt.auction = auction.key();
+        receipt.bidder = ctx.accounts.bidder.key();
+        receipt.index = auction.withdrawal_count;
+        receipt.bump = ctx.bumps.bid_withdrawal;
+        receipt.locked_slot = ctx.accounts.bid_escrow.locked_slot;
+
+        auction.bid_count = auction.bid_count.saturating_sub(1);
+        auction.withdrawal_count += 1;
 
+        // BidEscrow is closed by Anchor's `close = bidder` constraint,
+        // returning the locked SOL (and rent) to the bidder.
         Ok(())
     }
 
     /// Settle the auction using the verified result from arcium_program
     /// The auction_result account is created by arcium_program after reveal_winner
-    /// and contains the plaintext winner/winning_amount verified by MPC
-    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+    /// and contains the plaintext winner/winning_amount verified by MPC.
+    /// For multi-unit auctions this is a per-winner claim: it charges the uniform
+    /// clearing price and delivers one unit, and the last claim settles the auction.
+    /// Remaining accounts: one per verified Metaplex creator, in metadata order
+    /// (the creator wallet, or its payment token account for SPL auctions).
+    pub fn settle_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleAuction<'info>>,
+        cnft_root: Option<[u8; 32]>,
+    ) -> Result<()> {
         let now = Clock::get()?.unix_timestamp;
 
         require!(now > ctx.accounts.auction.end_time, AuctionError::AuctionNotEnded);
@@ -106,23 +560,50 @@ pub mod sealed_auction {
         // Read verified winner/amount from arcium_program's AuctionResult
         let winner = auction_result.winner;
         let winning_amount = auction_result.winning_amount;
+        // First-price pays the top bid, second-price pays the runner-up (floored at min_price)
+        let clearing_price = ctx.accounts.auction.clearing_price(auction_result);
 
-        // 4. Verify winner matches the provided winner account
-        require!(
-            winner == ctx.accounts.winner.key(),
-            AuctionError::AuctionMismatch
-        );
+        // 4. Verify winner matches the provided winner account.
+        // Each multi-unit winner claims their own unit with this instruction.
+        if ctx.accounts.auction.units > 1 {
+            require!(
+                auction_result.winners[..auction_result.winner_count as usize]
+                    .contains(&ctx.accounts.winner.key()),
+                AuctionError::AuctionMismatch
+            );
+        } else {
+            require!(
+                winner == ctx.accounts.winner.key(),
+                AuctionError::AuctionMismatch
+            );
+        }
 
         // --- Financial Safety ---
+        // Hidden-reserve auctions store min_price = 0; the MPC compared bids to the reserve
         require!(winning_amount >= ctx.accounts.auction.min_price, AuctionError::BelowMinPrice);
+        // The escrow always covers the clearing price: the circuits drop every bid
+        // above the collateral locked with it (recording the bidder as a defaulter),
+        // collateral only grows afterwards, and clearing_price <= winning_amount.
+        // A revealed winner therefore never blocks settlement with a short escrow.
+
+        // Protocol fee and creator royalties come out of the clearing price,
+        // the seller gets the rest
+        let fee = ctx.accounts.auction.protocol_fee(clearing_price);
+        let royalties = ctx.accounts.creator_royalties(clearing_price)?;
         require!(
-            winning_amount <= ctx.accounts.winner_bid_escrow.max_locked_amount,
-            AuctionError::InsufficientEscrow
+            ctx.remaining_accounts.len() >= royalties.len(),
+            AuctionError::InvalidCreatorAccounts
         );
+        let creator_accounts = &ctx.remaining_accounts[..royalties.len()];
+        let royalties = ctx.accounts.payable_royalties(royalties, creator_accounts)?;
+        let royalty_total: u64 = royalties.iter().map(|(_, amount)| amount).sum();
+        let seller_amount = clearing_price
+            .checked_sub(fee)
+            .and_then(|rest| rest.checked_sub(royalty_total))
+            .ok_or(AuctionError::RoyaltyExceedsPrice)?;
 
         // --- Transactions ---
-        // 1. Pay Seller
-        // Uses system_program::transfer with PDA signer
+        // 1. Pay Seller (and treasury)
         let auction_key = ctx.accounts.auction.key();
         let winner_key = ctx.accounts.winner.key();
 
@@ -134,32 +615,194 @@ pub mod sealed_auction {
         ];
         let signer_seeds = &[&seeds[..]];
 
-        system_program::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.winner_bid_escrow.to_account_info(),
-                    to: ctx.accounts.seller.to_account_info(),
+        if ctx.accounts.auction.payment_mint.is_some() {
+            let (Some(payment_mint), Some(escrow_vault), Some(seller_payment_account), Some(winner_payment_account), Some(payment_token_program)) = (
+                &ctx.accounts.payment_mint,
+                &ctx.accounts.winner_escrow_vault,
+                &ctx.accounts.seller_payment_account,
+                &ctx.accounts.winner_payment_account,
+                &ctx.accounts.payment_token_program,
+            ) else {
+                return err!(AuctionError::MissingPaymentAccounts);
+            };
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    payment_token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: escrow_vault.to_account_info(),
+                        mint: payment_mint.to_account_info(),
+                        to: seller_payment_account.to_account_info(),
+                        authority: ctx.accounts.winner_bid_escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                seller_amount,
+                payment_mint.decimals,
+            )?;
+
+            if fee > 0 {
+                let Some(treasury_payment_account) = &ctx.accounts.treasury_payment_account else {
+                    return err!(AuctionError::MissingPaymentAccounts);
+                };
+
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        payment_token_program.to_account_info(),
+                        token_interface::TransferChecked {
+                            from: escrow_vault.to_account_info(),
+                            mint: payment_mint.to_account_info(),
+                            to: treasury_payment_account.to_account_info(),
+                            authority: ctx.accounts.winner_bid_escrow.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    fee,
+                    payment_mint.decimals,
+                )?;
+            }
+
+            for ((_, amount), creator_account) in royalties.iter().zip(ctx.remaining_accounts) {
+                if *amount == 0 {
+                    continue;
+                }
+
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        payment_token_program.to_account_info(),
+                        token_interface::TransferChecked {
+                            from: escrow_vault.to_account_info(),
+                            mint: payment_mint.to_account_info(),
+                            to: creator_account.clone(),
+                            authority: ctx.accounts.winner_bid_escrow.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    *amount,
+                    payment_mint.decimals,
+                )?;
+            }
+
+            // 2. Refund Excess to Winner
+            // Remaining tokens (max_locked - clearing_price) go back to the winner,
+            // then the empty vault is closed and its rent returned to the winner.
+            let excess = escrow_vault.amount - clearing_price;
+            if excess > 0 {
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        payment_token_program.to_account_info(),
+                        token_interface::TransferChecked {
+                            from: escrow_vault.to_account_info(),
+                            mint: payment_mint.to_account_info(),
+                            to: winner_payment_account.to_account_info(),
+                            authority: ctx.accounts.winner_bid_escrow.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    excess,
+                    payment_mint.decimals,
+                )?;
+            }
+
+            token_interface::close_account(CpiContext::new_with_signer(
+                payment_token_program.to_account_info(),
+                token_interface::CloseAccount {
+                    account: escrow_vault.to_account_info(),
+                    destination: ctx.accounts.winner.to_account_info(),
+                    authority: ctx.accounts.winner_bid_escrow.to_account_info(),
                 },
                 signer_seeds,
-            ),
-            winning_amount,
-        )?;
+            ))?;
+        } else {
+            // The escrow PDA is program-owned and carries data, so the System Program
+            // cannot debit it; move the lamports directly instead
+            ctx.accounts.winner_bid_escrow.sub_lamports(clearing_price)?;
+            ctx.accounts.seller.add_lamports(seller_amount)?;
+            if fee > 0 {
+                let Some(treasury) = &ctx.accounts.treasury else {
+                    return err!(AuctionError::MissingTreasury);
+                };
+                treasury.add_lamports(fee)?;
+            }
+
+            for ((_, amount), creator_account) in royalties.iter().zip(ctx.remaining_accounts) {
+                if *amount > 0 {
+                    creator_account.add_lamports(*amount)?;
+                }
+            }
 
-        // 2. Refund Excess to Winner
-        // Handled automatically by Anchor's `close = winner` constraint.
-        // Any remaining lamports in winner_bid_escrow (max_locked - winning_amount)
-        // will be sent to the winner when the account closes at end of instruction.
+            // 2. Refund Excess to Winner
+            // Handled automatically by Anchor's `close = winner` constraint.
+            // Any remaining lamports in winner_bid_escrow (max_locked - clearing_price)
+            // will be sent to the winner when the account closes at end of instruction.
+        }
+
+        // NFT → winner, signed by the auction PDA that owns the escrow
+        let listing_id = ctx.accounts.auction.listing_id.to_le_bytes();
+        let auction_seeds = &[
+            b"auction".as_ref(),
+            ctx.accounts.auction.nft_mint.as_ref(),
+            listing_id.as_ref(),
+            &[ctx.accounts.auction.bump],
+        ];
+        let auction_signer = &[&auction_seeds[..]];
 
-        // NFT → winner
-        token::transfer(
-            ctx.accounts.into_transfer_to_winner(),
-            1,
+        // A merkle proof or bundle triples follow the creator accounts
+        release_asset(
+            &ctx.accounts.auction,
+            ctx.accounts.winner.to_account_info(),
+            ctx.accounts.winner_nft_account.as_deref(),
+            EscrowedAsset {
+                nft_mint: ctx.accounts.nft_mint.as_deref(),
+                nft_escrow_account: ctx.accounts.nft_escrow_account.as_deref(),
+                token_program: ctx.accounts.token_program.as_ref(),
+                bundle: ctx.accounts.bundle.as_deref(),
+                pnft: &ctx.accounts.pnft,
+                cnft: &ctx.accounts.cnft,
+                core: &ctx.accounts.core,
+                cnft_root,
+                payer: ctx.accounts.payer.to_account_info(),
+            },
+            &ctx.remaining_accounts[royalties.len()..],
+            Some(ctx.accounts.auction.unit_amount),
+            auction_signer,
         )?;
 
-        // Mark settled
         ctx.accounts.winner_bid_escrow.withdrawn = true;
-        ctx.accounts.auction.settled = true;
+
+        // Multi-unit auctions settle once every winner has claimed; units
+        // nobody won go back to the seller then
+        let units_claimed = ctx.accounts.auction.units_claimed + 1;
+        if ctx.accounts.auction.units == 1 || units_claimed >= ctx.accounts.auction_result.winner_count {
+            if let (Some(nft_mint), Some(nft_escrow_account), Some(token_program)) = (
+                &ctx.accounts.nft_mint,
+                &mut ctx.accounts.nft_escrow_account,
+                &ctx.accounts.token_program,
+            ) {
+                nft_escrow_account.reload()?;
+                if nft_escrow_account.amount > 0 {
+                    let Some(seller_nft_account) = &ctx.accounts.seller_nft_account else {
+                        return err!(AuctionError::MissingNftAccounts);
+                    };
+                    token_interface::transfer_checked(
+                        CpiContext::new_with_signer(
+                            token_program.to_account_info(),
+                            token_interface::TransferChecked {
+                                from: nft_escrow_account.to_account_info(),
+                                mint: nft_mint.to_account_info(),
+                                to: seller_nft_account.to_account_info(),
+                                authority: ctx.accounts.auction.to_account_info(),
+                            },
+                            auction_signer,
+                        ),
+                        nft_escrow_account.amount,
+                        nft_mint.decimals,
+                    )?;
+                }
+            }
+            ctx.accounts.auction.settled = true;
+        }
+        ctx.accounts.auction.units_claimed = units_claimed;
 
         Ok(())
     } 
@@ -171,33 +814,291 @@ pub mod sealed_auction {
         require!(!escrow.withdrawn, AuctionError::AlreadyWithdrawn);
         require!(auction.settled, AuctionError::AuctionNotSettled);
 
+        // A revealed defaulter is only refunded what slash_defaulter leaves.
+        // Expired auctions never revealed a result, so there is nothing to check.
+        if !auction.expired {
+            let Some(auction_result) = &ctx.accounts.auction_result else {
+                return err!(AuctionError::InvalidAuctionResult);
+            };
+            require!(
+                escrow.slashed || !auction_result.is_defaulter(&escrow.bidder),
+                AuctionError::SlashPending
+            );
+        }
+
         escrow.withdrawn = true;
-        
-        // Account is closed automatically, sending all funds to bidder.
+
+        if auction.payment_mint.is_some() {
+            // Return every locked token, then close the vault
+            let (Some(payment_mint), Some(escrow_vault), Some(bidder_payment_account), Some(token_program)) = (
+                &ctx.accounts.payment_mint,
+                &ctx.accounts.escrow_vault,
+                &ctx.accounts.bidder_payment_account,
+                &ctx.accounts.payment_token_program,
+            ) else {
+                return err!(AuctionError::MissingPaymentAccounts);
+            };
+
+            let auction_key = auction.key();
+            let bidder_key = ctx.accounts.bidder.key();
+            let seeds = &[
+                b"bid_escrow",
+                auction_key.as_ref(),
+                bidder_key.as_ref(),
+                &[escrow.bump],
+            ];
+            let signer_seeds = &[&seeds[..]];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: escrow_vault.to_account_info(),
+                        mint: payment_mint.to_account_info(),
+                        to: bidder_payment_account.to_account_info(),
+                        authority: escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                escrow_vault.amount,
+                payment_mint.decimals,
+            )?;
+
+            token_interface::close_account(CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_interface::CloseAccount {
+                    account: escrow_vault.to_account_info(),
+                    destination: ctx.accounts.bidder.to_account_info(),
+                    authority: escrow.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+
+        // Account is closed automatically, sending all remaining lamports to bidder.
         Ok(())
     }
 
+    /// Refund every bidder listed in remaining accounts, closing each escrow to
+    /// its bidder: (bidder, bid_escrow) pairs for SOL auctions, and
+    /// (bidder, bid_escrow, bid_vault, bidder token account) groups for SPL ones.
+    /// Winners, withdrawn escrows, unslashed defaulters and escrows that are
+    /// already closed are skipped, so a crank racing other refunds still succeeds.
+    /// Any other mismatch (wrong escrow PDA, vault or token account, or a
+    /// read-only bidder) fails the whole batch.
+    pub fn refund_losers_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundLosersBatch<'info>>,
+    ) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        require!(auction.settled, AuctionError::AuctionNotSettled);
+
+        let spl = match (&ctx.accounts.payment_mint, &ctx.accounts.payment_token_program) {
+            (Some(payment_mint), Some(token_program)) => Some((payment_mint, token_program)),
+            (None, None) if auction.payment_mint.is_none() => None,
+            _ => return err!(AuctionError::MissingPaymentAccounts),
+        };
+        let group_len = if spl.is_some() { 4 } else { 2 };
+        require!(ctx.remaining_accounts.len() % group_len == 0, AuctionError::InvalidRefundBatch);
+
+        // Same rule as refund_loser: only expired auctions come without a result
+        let auction_result = match &ctx.accounts.auction_result {
+            Some(auction_result) => Some(auction_result),
+            None if auction.expired => None,
+            None => return err!(AuctionError::InvalidAuctionResult),
+        };
+
+        let auction_key = auction.key();
+        for group in ctx.remaining_accounts.chunks(group_len) {
+            let (bidder_info, escrow_info) = (&group[0], &group[1]);
+            if escrow_info.data_is_empty() {
+                continue;
+            }
+
+            let escrow = Account::<BidEscrow>::try_from(escrow_info)?;
+            let expected = Pubkey::create_program_address(
+                &[b"bid_escrow", auction_key.as_ref(), bidder_info.key.as_ref(), &[escrow.bump]],
+                ctx.program_id,
+            )
+            .map_err(|_| error!(AuctionError::InvalidRefundBatch))?;
+            require_keys_eq!(expected, escrow_info.key(), AuctionError::InvalidRefundBatch);
+            require!(
+                escrow.bidder == bidder_info.key() && escrow.auction == auction_key,
+                AuctionError::InvalidRefundBatch
+            );
+            require!(bidder_info.is_writable, AuctionError::InvalidRefundBatch);
+
+            if escrow.withdrawn {
+                continue;
+            }
+            if let Some(auction_result) = auction_result {
+                if auction_result.is_winner(&escrow.bidder)
+                    || (!escrow.slashed && auction_result.is_defaulter(&escrow.bidder))
+                {
+                    continue;
+                }
+            }
+
+            // Return every locked token, then close the vault
+            if let Some((payment_mint, token_program)) = spl {
+                let (vault_info, destination_info) = (&group[2], &group[3]);
+                let (expected_vault, _) = Pubkey::find_program_address(
+                    &[b"bid_vault", escrow_info.key.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(expected_vault, vault_info.key(), AuctionError::InvalidRefundBatch);
+                let vault = InterfaceAccount::<token_interface::TokenAccount>::try_from(vault_info)?;
+                let destination = InterfaceAccount::<token_interface::TokenAccount>::try_from(destination_info)?;
+                require!(
+                    destination.owner == bidder_info.key() && destination.mint == payment_mint.key(),
+                    AuctionError::InvalidRefundBatch
+                );
+
+                let seeds = &[
+                    b"bid_escrow",
+                    auction_key.as_ref(),
+                    bidder_info.key.as_ref(),
+                    &[escrow.bump],
+                ];
+                let signer_seeds = &[&seeds[..]];
+
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        token_interface::TransferChecked {
+                            from: vault_info.clone(),
+                            mint: payment_mint.to_account_info(),
+                            to: destination_info.clone(),
+                            authority: escrow_info.clone(),
+                        },
+                        signer_seeds,
+                    ),
+                    vault.amount,
+                    payment_mint.decimals,
+                )?;
+
+                token_interface::close_account(CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    token_interface::CloseAccount {
+                        account: vault_info.clone(),
+                        destination: bidder_info.clone(),
+                        authority: escrow_info.clone(),
+                    },
+                    signer_seeds,
+                ))?;
+            }
 
-    pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+            escrow.close(bidder_info.clone())?;
+        }
+
+        Ok(())
+    }
+
+    /// Pay the seller the auction's `slash_bps` share of a bidder's collateral
+    /// once arcium_program revealed them bidding above it. Permissionless;
+    /// refund_loser returns the rest afterwards.
+    pub fn slash_defaulter(ctx: Context<SlashDefaulter>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        require!(auction.settled, AuctionError::AuctionNotSettled);
+        require!(
+            ctx.accounts.auction_result.is_defaulter(&ctx.accounts.bidder.key()),
+            AuctionError::NotADefaulter
+        );
+        require!(!ctx.accounts.bid_escrow.withdrawn, AuctionError::AlreadyWithdrawn);
+        require!(!ctx.accounts.bid_escrow.slashed, AuctionError::AlreadySlashed);
+
+        let amount = auction.slash_amount(ctx.accounts.bid_escrow.max_locked_amount);
+
+        if amount > 0 {
+            if auction.payment_mint.is_some() {
+                let (Some(payment_mint), Some(escrow_vault), Some(seller_payment_account), Some(token_program)) = (
+                    &ctx.accounts.payment_mint,
+                    &ctx.accounts.escrow_vault,
+                    &ctx.accounts.seller_payment_account,
+                    &ctx.accounts.payment_token_program,
+                ) else {
+                    return err!(AuctionError::MissingPaymentAccounts);
+                };
+
+                let auction_key = auction.key();
+                let bidder_key = ctx.accounts.bidder.key();
+                let seeds = &[
+                    b"bid_escrow",
+                    auction_key.as_ref(),
+                    bidder_key.as_ref(),
+                    &[ctx.accounts.bid_escrow.bump],
+                ];
+                let signer_seeds = &[&seeds[..]];
+
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        token_interface::TransferChecked {
+                            from: escrow_vault.to_account_info(),
+                            mint: payment_mint.to_account_info(),
+                            to: seller_payment_account.to_account_info(),
+                            authority: ctx.accounts.bid_escrow.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    amount,
+                    payment_mint.decimals,
+                )?;
+            } else {
+                ctx.accounts.bid_escrow.sub_lamports(amount)?;
+                ctx.accounts.seller.add_lamports(amount)?;
+            }
+        }
+
+        let escrow = &mut ctx.accounts.bid_escrow;
+        escrow.max_locked_amount -= amount;
+        escrow.slashed = true;
+
+        Ok(())
+    }
+
+    pub fn cancel_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelAuction<'info>>,
+        cnft_root: Option<[u8; 32]>,
+    ) -> Result<()> {
         let auction = &ctx.accounts.auction;
         let now = Clock::get()?.unix_timestamp;
 
         // Safety checks
-        require!(auction.bid_count == 0, AuctionError::BidsAlreadyPlaced);
+        // Before start_time no bids can exist, so a scheduled drop can always be pulled
+        if now >= auction.start_time {
+            require!(auction.bid_count == 0, AuctionError::BidsAlreadyPlaced);
+        }
         require!(!auction.settled, AuctionError::AlreadySettled);
         require!(now < auction.end_time, AuctionError::AuctionEnded);
 
         // Transfer NFT back to seller
+        let listing_id = auction.listing_id.to_le_bytes();
         let seeds = &[
             b"auction".as_ref(),
             auction.nft_mint.as_ref(),
+            listing_id.as_ref(),
             &[auction.bump],
         ];
         let signer = &[&seeds[..]];
 
-        token::transfer(
-            ctx.accounts.into_transfer_back_to_seller().with_signer(signer),
-            1,
+        release_asset(
+            &ctx.accounts.auction,
+            ctx.accounts.seller.to_account_info(),
+            ctx.accounts.seller_nft_account.as_deref(),
+            EscrowedAsset {
+                nft_mint: ctx.accounts.nft_mint.as_deref(),
+                nft_escrow_account: ctx.accounts.nft_escrow_account.as_deref(),
+                token_program: ctx.accounts.token_program.as_ref(),
+                bundle: ctx.accounts.bundle.as_deref(),
+                pnft: &ctx.accounts.pnft,
+                cnft: &ctx.accounts.cnft,
+                core: &ctx.accounts.core,
+                cnft_root,
+                payer: ctx.accounts.seller.to_account_info(),
+            },
+            ctx.remaining_accounts,
+            None,
+            signer,
         )?;
 
         Ok(())
@@ -206,7 +1107,10 @@ pub mod sealed_auction {
     /// Finalize auction when Arcium determines no valid winner exists
     /// (all bids < min_price or no bids). Permissionless - anyone can crank.
     /// The auction_result account must show winner = Pubkey::default() (all zeros)
-    pub fn finalize_no_winner(ctx: Context<FinalizeNoWinner>) -> Result<()> {
+    pub fn finalize_no_winner<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeNoWinner<'info>>,
+        cnft_root: Option<[u8; 32]>,
+    ) -> Result<()> {
         let now = Clock::get()?.unix_timestamp;
 
         require!(now > ctx.accounts.auction.end_time, AuctionError::AuctionNotEnded);
@@ -237,16 +1141,33 @@ pub mod sealed_auction {
         );
 
         // Transfer NFT back to seller
+        let listing_id = ctx.accounts.auction.listing_id.to_le_bytes();
         let seeds = &[
             b"auction".as_ref(),
             ctx.accounts.auction.nft_mint.as_ref(),
+            listing_id.as_ref(),
             &[ctx.accounts.auction.bump],
         ];
         let signer = &[&seeds[..]];
 
-        token::transfer(
-            ctx.accounts.into_transfer_back_to_seller().with_signer(signer),
-            1,
+        release_asset(
+            &ctx.accounts.auction,
+            ctx.accounts.seller.to_account_info(),
+            ctx.accounts.seller_nft_account.as_deref(),
+            EscrowedAsset {
+                nft_mint: ctx.accounts.nft_mint.as_deref(),
+                nft_escrow_account: ctx.accounts.nft_escrow_account.as_deref(),
+                token_program: ctx.accounts.token_program.as_ref(),
+                bundle: ctx.accounts.bundle.as_deref(),
+                pnft: &ctx.accounts.pnft,
+                cnft: &ctx.accounts.cnft,
+                core: &ctx.accounts.core,
+                cnft_root,
+                payer: ctx.accounts.payer.to_account_info(),
+            },
+            ctx.remaining_accounts,
+            None,
+            signer,
         )?;
 
         // Mark as settled so bidders can call refund_loser
@@ -260,7 +1181,10 @@ pub mod sealed_auction {
     /// - cancel_auction requires auction NOT ended
     /// - finalize_no_winner requires AuctionResult (which requires bids to exist)
     /// Only the seller can call this.
-    pub fn reclaim_unsold(ctx: Context<ReclaimUnsold>) -> Result<()> {
+    pub fn reclaim_unsold<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReclaimUnsold<'info>>,
+        cnft_root: Option<[u8; 32]>,
+    ) -> Result<()> {
         let auction = &ctx.accounts.auction;
         let now = Clock::get()?.unix_timestamp;
 
@@ -270,16 +1194,33 @@ pub mod sealed_auction {
         require!(!auction.settled, AuctionError::AlreadySettled);
 
         // Transfer NFT back to seller
+        let listing_id = auction.listing_id.to_le_bytes();
         let seeds = &[
             b"auction".as_ref(),
             auction.nft_mint.as_ref(),
+            listing_id.as_ref(),
             &[auction.bump],
         ];
         let signer = &[&seeds[..]];
 
-        token::transfer(
-            ctx.accounts.into_transfer_back_to_seller().with_signer(signer),
-            1,
+        release_asset(
+            &ctx.accounts.auction,
+            ctx.accounts.seller.to_account_info(),
+            ctx.accounts.seller_nft_account.as_deref(),
+            EscrowedAsset {
+                nft_mint: ctx.accounts.nft_mint.as_deref(),
+                nft_escrow_account: ctx.accounts.nft_escrow_account.as_deref(),
+                token_program: ctx.accounts.token_program.as_ref(),
+                bundle: ctx.accounts.bundle.as_deref(),
+                pnft: &ctx.accounts.pnft,
+                cnft: &ctx.accounts.cnft,
+                core: &ctx.accounts.core,
+                cnft_root,
+                payer: ctx.accounts.seller.to_account_info(),
+            },
+            ctx.remaining_accounts,
+            None,
+            signer,
         )?;
 
         // Account is closed by Anchor's `close = seller` constraint
@@ -288,11 +1229,122 @@ pub mod sealed_auction {
         Ok(())
     }
 
-    /// Close a settled auction account to reclaim rent.
-    /// Use this to clean up old settled auctions that weren't closed properly.
-    pub fn close_settled(_ctx: Context<CloseSettled>) -> Result<()> {
-        // Account is closed automatically by the `close = seller` constraint
+    /// Unwind an auction whose result was never revealed by Arcium
+    /// (cluster down, aborted computation, failed callback).
+    /// Permissionless. After end_time + resolution_grace_period the NFT goes
+    /// back to the seller and the auction is marked settled and expired,
+    /// so every bidder, including the would-be winner, can call refund_loser.
+    pub fn expire_unresolved<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExpireUnresolved<'info>>,
+        cnft_root: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(!auction.settled, AuctionError::AlreadySettled);
+        require!(
+            now > auction.end_time.saturating_add(auction.resolution_grace_period),
+            AuctionError::GracePeriodNotElapsed
+        );
+
+        // A revealed result must go through settle_auction / finalize_no_winner.
+        // The PDA may not exist at all if reveal_winner was never queued.
+        let result_info = ctx.accounts.auction_result.to_account_info();
+        if result_info.owner == &ARCIUM_PROGRAM_ID && !result_info.data_is_empty() {
+            let data = result_info.try_borrow_data()?;
+            let auction_result = AuctionResult::try_deserialize(&mut &data[..])?;
+            require!(!auction_result.revealed, AuctionError::ResultAlreadyRevealed);
+        }
+
+        // Transfer NFT back to seller
+        let listing_id = auction.listing_id.to_le_bytes();
+        let seeds = &[
+            b"auction".as_ref(),
+            auction.nft_mint.as_ref(),
+            listing_id.as_ref(),
+            &[auction.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        release_asset(
+            &ctx.accounts.auction,
+            ctx.accounts.seller.to_account_info(),
+            ctx.accounts.seller_nft_account.as_deref(),
+            EscrowedAsset {
+                nft_mint: ctx.accounts.nft_mint.as_deref(),
+                nft_escrow_account: ctx.accounts.nft_escrow_account.as_deref(),
+                token_program: ctx.accounts.token_program.as_ref(),
+                bundle: ctx.accounts.bundle.as_deref(),
+                pnft: &ctx.accounts.pnft,
+                cnft: &ctx.accounts.cnft,
+                core: &ctx.accounts.core,
+                cnft_root,
+                payer: ctx.accounts.payer.to_account_info(),
+            },
+            ctx.remaining_accounts,
+            None,
+            signer,
+        )?;
+
+        // Mark as settled so bidders can call refund_loser
+        ctx.accounts.auction.settled = true;
+        ctx.accounts.auction.expired = true;
+
+        Ok(())
+    }
+
+    /// Return the rent of a BidWithdrawal receipt to its bidder once arcium_program
+    /// is done with it: the reveal has been queued, or the auction was unwound by
+    /// expire_unresolved or closed without a reveal. Permissionless.
+    pub fn close_bid_withdrawal(ctx: Context<CloseBidWithdrawal>) -> Result<()> {
+        if ctx.accounts.auction_result.is_none() {
+            let info = &ctx.accounts.auction;
+            if !info.data_is_empty() {
+                require_keys_eq!(*info.owner, crate::ID, AuctionError::WithdrawalPending);
+                let auction = Auction::try_deserialize(&mut &info.data.borrow()[..])?;
+                require!(auction.expired, AuctionError::WithdrawalPending);
+            }
+        }
+
+        // Account is closed automatically by the `close = bidder` constraint
         Ok(())
     }
 
 }
+
+/// Checks shared by create_auction, create_compressed_auction and create_core_auction
+fn validate_listing(
+    min_price: u64,
+    start_time: i64,
+    end_time: i64,
+    hidden_reserve: bool,
+    resolution_grace_period: i64,
+    soft_close: &Option<SoftClose>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(end_time > now, AuctionError::InvalidEndTime);
+    // A start_time in the past opens bidding immediately
+    require!(start_time < end_time, AuctionError::InvalidStartTime);
+    require!(
+        resolution_grace_period >= MIN_RESOLUTION_GRACE_PERIOD,
+        AuctionError::InvalidGracePeriod
+    );
+    if let Some(soft_close) = soft_close {
+        require!(
+            soft_close.window > 0
+                && soft_close.extension > 0
+                && soft_close.max_end_time >= end_time,
+            AuctionError::InvalidSoftClose
+        );
+    }
+    // A hidden reserve is submitted once, encrypted, to arcium_program's
+    // initialize_auction_state; nothing about it may be stored in plaintext
+    if hidden_reserve {
+        require!(min_price == 0, AuctionError::InvalidMinPrice);
+    } else {
+        require!(min_price > 0, AuctionError::InvalidMinPrice);
+    }
+
+    Ok(())
+}
@@ -6,15 +6,198 @@ pub const ARCIUM_PROGRAM_ID: Pubkey = pubkey!("2excUVgCNGZDN4yHGBbxBg4ptNYTH1nyq
 /// Seed for auction result PDA (must match arcium_program)
 pub const AUCTION_RESULT_SEED: &[u8] = b"auction_result";
 
+/// Shortest grace period (in seconds) after end_time before an auction
+/// without a revealed result can be expired
+pub const MIN_RESOLUTION_GRACE_PERIOD: i64 = 24 * 60 * 60;
+
+/// Most units a multi-unit auction can sell; bounded by the size of the
+/// encrypted top-N state arcium_program keeps (must match arcium_program)
+pub const MAX_UNITS: usize = 4;
+
+/// Most extra NFTs a bundle can carry besides the auction's nft_mint;
+/// every item is moved in the same transaction, so this is bounded by account limits
+pub const MAX_BUNDLE_ITEMS: usize = 8;
+
+/// Most over-collateral bidders arcium_program tracks and reveals per auction
+/// (must match arcium_program and the circuits)
+pub const MAX_DEFAULTERS: usize = 4;
+
+/// Bids arcium_program keeps ranked per auction (must match arcium_program and the circuits).
+/// Once more escrows than this have been locked the lowest bids may have been
+/// dropped from the ranking, so withdrawals could let one of them overtake a kept bid.
+pub const RANKED_BIDS: u64 = 8;
+
+/// Basis-point denominator for fees
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Metaplex Bubblegum program, owner of compressed NFT leaves
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+
+/// SPL Account Compression program, owner of the merkle trees
+pub const ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// SPL Noop program Bubblegum logs leaf changes through
+pub const NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Metaplex Core program, owner of Core assets
+pub const MPL_CORE_PROGRAM_ID: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+
+/// Highest protocol fee the admin can set (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Global protocol settings, a singleton PDA at [b"config"]
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Protocol fee taken from the clearing price at settlement
+    pub fee_bps: u16,
+    /// Receives the protocol fee (wallet; token fees go to its token account)
+    pub treasury: Pubkey,
+    /// Share of a defaulter's locked collateral slash_defaulter pays to the seller
+    pub slash_bps: u16,
+    pub bump: u8,
+}
+
+/// How the winner's price is determined at settlement
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuctionType {
+    /// Winner pays their own bid
+    FirstPrice,
+    /// Winner pays the second-highest valid bid (Vickrey),
+    /// or the reserve when there is only one valid bid
+    SecondPrice,
+}
+
+/// How the auctioned asset is held and moved
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    /// SPL Token / Token-2022 NFT moved with transfer_checked
+    Spl,
+    /// Metaplex programmable NFT moved with Token Metadata's Transfer
+    ProgrammableNft,
+    /// Bubblegum compressed NFT; the auction PDA owns the leaf
+    Compressed,
+    /// Metaplex Core asset; the auction PDA is the asset owner
+    Core,
+}
+
+/// Leaf of a compressed NFT held by the auction. Everything Bubblegum needs
+/// to rebuild the leaf hash except the owner, which is always the auction PDA.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CompressedNft {
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
+/// Multi-unit listing: `units` identical units of `unit_amount` tokens each
+/// (SFT editions or fungible lots), sold at one uniform clearing price
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Lot {
+    pub units: u8,
+    pub unit_amount: u64,
+}
+
+/// Anti-sniping: a lock_bid_funds in the last `window` seconds
+/// pushes end_time out by `extension`, never past `max_end_time`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SoftClose {
+    pub window: i64,
+    pub extension: i64,
+    pub max_end_time: i64,
+}
+
 #[account]
 pub struct Auction {
     pub seller: Pubkey,
+    /// NFT mint, the Bubblegum asset id for compressed NFTs,
+    /// or the asset account for Core assets
     pub nft_mint: Pubkey,
+    /// Seller-chosen id, part of the PDA seeds so the same mint can be listed again
+    pub listing_id: u64,
     pub min_price: u64,
+    /// Bidding opens at start_time; before it the seller may cancel freely
+    pub start_time: i64,
     pub end_time: i64,
     pub settled: bool,
     pub bid_count: u64,
     pub bump: u8,
+    pub auction_type: AuctionType,
+    /// SPL mint bids are paid in, or None for SOL
+    pub payment_mint: Option<Pubkey>,
+    /// Reserve is held only as MXE ciphertext in arcium_program (min_price is 0)
+    pub hidden_reserve: bool,
+    /// Seconds after end_time the Arcium reveal has before expire_unresolved is allowed
+    pub resolution_grace_period: i64,
+    /// Set by expire_unresolved: no result was revealed and every bidder is refunded
+    pub expired: bool,
+    /// Number of withdraw_bid calls; each leaves a BidWithdrawal receipt at this index
+    pub withdrawal_count: u64,
+    /// Optional soft-close; end_time above already includes any extensions
+    pub soft_close: Option<SoftClose>,
+    /// Protocol fee and treasury snapshotted from Config at creation
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub asset_kind: AssetKind,
+    /// Set for AssetKind::Compressed
+    pub compressed: Option<CompressedNft>,
+    /// Units for sale (1 unless listed as a Lot) and tokens delivered per unit
+    pub units: u8,
+    pub unit_amount: u64,
+    /// Winners of a multi-unit auction that have settled so far
+    pub units_claimed: u8,
+    /// Extra NFTs sold together with nft_mint, recorded in the Bundle PDA
+    pub bundle_items: u8,
+    /// Slash share snapshotted from Config at creation
+    pub slash_bps: u16,
+}
+
+impl Auction {
+    /// Account size with the discriminator, field by field
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 1 + 33 + 1 + 8 + 1 + 8
+        + 25 + 2 + 32 + 1 + 141 + 1 + 8 + 1 + 1 + 2;
+
+    /// The amount the winner is charged for a revealed result.
+    /// For hidden-reserve auctions the MPC already floors second_amount at the reserve.
+    /// Multi-unit auctions get the uniform price straight from the MPC.
+    pub fn clearing_price(&self, result: &AuctionResult) -> u64 {
+        if self.units > 1 {
+            return result.winning_amount;
+        }
+        match self.auction_type {
+            AuctionType::FirstPrice => result.winning_amount,
+            AuctionType::SecondPrice => result.second_amount.max(self.min_price),
+        }
+    }
+
+    /// Protocol fee owed to the treasury out of `amount` (rounded down)
+    pub fn protocol_fee(&self, amount: u64) -> u64 {
+        (amount as u128 * self.fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Part of a defaulter's `collateral` paid to the seller (rounded down)
+    pub fn slash_amount(&self, collateral: u64) -> u64 {
+        (collateral as u128 * self.slash_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+/// One extra NFT of a bundle and the auction-owned token account escrowing it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct BundleItem {
+    pub mint: Pubkey,
+    pub escrow: Pubkey,
+}
+
+/// The rest of a bundle auction's set; nft_mint stays the primary item.
+/// Seeds: [b"bundle", auction]
+#[account]
+pub struct Bundle {
+    pub auction: Pubkey,
+    pub items: Vec<BundleItem>,
+    pub bump: u8,
 }
 
 #[account]
@@ -24,11 +207,31 @@ pub struct BidEscrow {
     pub max_locked_amount: u64,
     pub withdrawn: bool,
     pub bump: u8,
+    /// Set by slash_defaulter; the rest of the collateral can then be refunded
+    pub slashed: bool,
+    /// Slot of lock_bid_funds; the circuits rank equal bids by the earlier slot.
+    /// Topping up collateral keeps it.
+    pub locked_slot: u64,
 }
 
-/// AuctionResult account created by arcium_program after reveal_winner
-/// This is a cross-program account - we read it but don't own it
+/// Receipt left by withdraw_bid so arcium_program can drop the bid
+/// from the encrypted running state. Processed in `index` order.
 #[account]
+pub struct BidWithdrawal {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub index: u64,
+    pub bump: u8,
+    /// The withdrawn escrow's locked_slot; a later escrow of the same bidder
+    /// always has a later one, so its bid is not retracted
+    pub locked_slot: u64,
+}
+
+/// AuctionResult account created by arcium_program after reveal_winner
+/// This is a cross-program account - we read it but don't own it.
+/// The namespace keeps `#[account]` from claiming it for this program;
+/// the discriminator is arcium_program's ("account:AuctionResult").
+#[account("arcium_program", discriminator = [182, 105, 71, 113, 228, 147, 117, 135])]
 pub struct AuctionResult {
     /// The auction this result belongs to
     pub auction_id: Pubkey,
@@ -38,8 +241,40 @@ pub struct AuctionResult {
     pub revealed: bool,
     /// The winner's pubkey (plaintext)
     pub winner: Pubkey,
-    /// The winning bid amount in lamports (plaintext)
+    /// The winning bid amount in lamports (plaintext);
+    /// the uniform clearing price for multi-unit auctions
     pub winning_amount: u64,
     /// Timestamp when revealed
     pub revealed_at: i64,
+    /// The second-highest valid bid floored at the reserve, in lamports;
+    /// 0 for first-price auctions
+    pub second_amount: u64,
+    /// Number of reveal computations queued
+    pub attempts: u32,
+    /// Offset of the most recently queued reveal computation
+    pub last_computation_offset: u64,
+    /// Multi-unit winners, highest bid first; the first `winner_count` are set.
+    /// Single-unit results hold `winner` alone.
+    pub winner_count: u8,
+    pub winners: [Pubkey; MAX_UNITS],
+    /// Bidders caught bidding above their locked collateral;
+    /// the first `defaulter_count` are set
+    pub defaulter_count: u8,
+    pub defaulters: [Pubkey; MAX_DEFAULTERS],
+}
+
+impl Owner for AuctionResult {
+    fn owner() -> Pubkey {
+        ARCIUM_PROGRAM_ID
+    }
+}
+
+impl AuctionResult {
+    pub fn is_defaulter(&self, bidder: &Pubkey) -> bool {
+        self.defaulters[..self.defaulter_count as usize].contains(bidder)
+    }
+
+    pub fn is_winner(&self, bidder: &Pubkey) -> bool {
+        self.winner == *bidder || self.winners[..self.winner_count as usize].contains(bidder)
+    }
 }
@@ -5,9 +5,50 @@ import {
   createMint,
   getOrCreateAssociatedTokenAccount,
   mintTo,
-  getAssociatedTokenAddress
+  createAccount,
+  getAccount,
+  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID
 } from "@solana/spl-token";
+import { generateSigner, keypairIdentity, none, percentAmount, publicKey, publicKeyBytes } from "@metaplex-foundation/umi";
+import { createUmi } from "@metaplex-foundation/umi-bundle-defaults";
+import { fromWeb3JsKeypair, fromWeb3JsPublicKey, toWeb3JsPublicKey } from "@metaplex-foundation/umi-web3js-adapters";
+import { createProgrammableNft, mplTokenMetadata } from "@metaplex-foundation/mpl-token-metadata";
+import {
+  createTree,
+  fetchMerkleTree,
+  findTreeConfigPda,
+  getCurrentRoot,
+  getMerkleProof,
+  getMerkleRoot,
+  hashLeaf,
+  MetadataArgsArgs,
+  mintV1,
+  mplBubblegum,
+  MPL_BUBBLEGUM_PROGRAM_ID,
+  parseLeafFromMintV1Transaction,
+  SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+  SPL_NOOP_PROGRAM_ID,
+} from "@metaplex-foundation/mpl-bubblegum";
+import { create as createCoreAsset, fetchAsset, mplCore, MPL_CORE_PROGRAM_ID } from "@metaplex-foundation/mpl-core";
 import { assert } from "chai";
+import * as fs from "fs";
+
+/// Keypairs behind the revealed AuctionResult loaded from tests/fixtures (see Anchor.toml)
+function fixtureKeypair(name: string): anchor.web3.Keypair {
+  const secretKey = JSON.parse(fs.readFileSync(`tests/fixtures/${name}.json`).toString());
+  return anchor.web3.Keypair.fromSecretKey(Uint8Array.from(secretKey));
+}
+
+const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Token Metadata PDA of `mint` (metadata, edition or token record, depending on `seeds`)
+function tokenMetadataPda(mint: anchor.web3.PublicKey, ...seeds: Buffer[]): anchor.web3.PublicKey {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), ...seeds],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+}
 
 describe("sealed_auction", () => {
   const provider = anchor.AnchorProvider.env();
@@ -16,14 +57,26 @@ describe("sealed_auction", () => {
   const program = anchor.workspace.sealedAuction as Program<SealedAuction>;
 
   const seller = anchor.web3.Keypair.generate();
-  const bidder1 = anchor.web3.Keypair.generate();
+  // The fixture result names bidder 1 the winner of nftMint's listing 1
+  const bidder1 = fixtureKeypair("bidder1");
   const bidder2 = anchor.web3.Keypair.generate();
+  const bidder3 = anchor.web3.Keypair.generate();
 
-  const nftEscrow = anchor.web3.Keypair.generate();
+  // The auction PDA's ATA for the NFT
+  let nftEscrow: anchor.web3.PublicKey;
+  const treasury = anchor.web3.Keypair.generate();
+  const feeBps = 250; // 2.5%
+  const slashBps = 1_000; // 10%
+
+  const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
 
   let nftMint: anchor.web3.PublicKey;
   let sellerNftAccount: anchor.web3.PublicKey;
   let auctionPda: anchor.web3.PublicKey;
+  const listingId = new anchor.BN(1);
 
   // Auction params
   const minPrice = new anchor.BN(1_000_000_000); // 1 SOL
@@ -34,6 +87,7 @@ describe("sealed_auction", () => {
     await confirmTx(await provider.connection.requestAirdrop(seller.publicKey, 10 * anchor.web3.LAMPORTS_PER_SOL));
     await confirmTx(await provider.connection.requestAirdrop(bidder1.publicKey, 10 * anchor.web3.LAMPORTS_PER_SOL));
     await confirmTx(await provider.connection.requestAirdrop(bidder2.publicKey, 10 * anchor.web3.LAMPORTS_PER_SOL));
+    await confirmTx(await provider.connection.requestAirdrop(bidder3.publicKey, 10 * anchor.web3.LAMPORTS_PER_SOL));
 
     // Create Mint
     nftMint = await createMint(
@@ -41,7 +95,8 @@ describe("sealed_auction", () => {
       seller,
       seller.publicKey,
       null,
-      0
+      0,
+      fixtureKeypair("nft-mint")
     );
 
     // Get ATA
@@ -64,27 +119,53 @@ describe("sealed_auction", () => {
     );
   });
 
+  it("Initializes protocol config", async () => {
+    const [programData] = anchor.web3.PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+
+    // The test wallet deployed the program, so it is the upgrade authority
+    await program.methods
+      .initializeConfig(provider.wallet.publicKey, feeBps, treasury.publicKey, slashBps)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        config: configPda,
+        program: program.programId,
+        programData: programData,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const config = await program.account.config.fetch(configPda);
+    assert.equal(config.feeBps, feeBps);
+    assert.equal(config.slashBps, slashBps);
+    assert.ok(config.treasury.equals(treasury.publicKey));
+  });
+
   it("Creates an Auction", async () => {
     const now = Math.floor(Date.now() / 1000);
-    endTime = new anchor.BN(now + 5); // 5 seconds duration
+    endTime = new anchor.BN(now + 10); // 10 seconds duration
 
     [auctionPda] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("auction"), nftMint.toBuffer()],
+      [Buffer.from("auction"), nftMint.toBuffer(), listingId.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
+    nftEscrow = await getAssociatedTokenAddress(nftMint, auctionPda, true);
 
     await program.methods
-      .createAuction(minPrice, endTime)
+      .createAuction(listingId, minPrice, new anchor.BN(0), endTime, { firstPrice: {} }, false, new anchor.BN(86_400), null, null)
       .accounts({
         seller: seller.publicKey,
         auction: auctionPda,
+        config: configPda,
         nftMint: nftMint,
         sellerNftAccount: sellerNftAccount,
-        nftEscrowAccount: nftEscrow.publicKey,
+        nftEscrowAccount: nftEscrow,
         tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
-      .signers([seller, nftEscrow])
+      .signers([seller])
       .rpc();
 
     // Verify state
@@ -93,10 +174,464 @@ describe("sealed_auction", () => {
     assert.ok(auctionAccount.minPrice.eq(minPrice));
 
     // Verify NFT moved
-    const escrowBalance = await provider.connection.getTokenAccountBalance(nftEscrow.publicKey);
+    const escrowBalance = await provider.connection.getTokenAccountBalance(nftEscrow);
     assert.equal(escrowBalance.value.uiAmount, 1);
   });
 
+  it("Escrows every unit of a multi-unit lot and returns them on cancel", async () => {
+    const editionMint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+    const sellerEditionAccount = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, seller, editionMint, seller.publicKey)
+    ).address;
+    await mintTo(provider.connection, seller, editionMint, sellerEditionAccount, seller, 3);
+
+    const [lotAuctionPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), editionMint.toBuffer(), listingId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const lotEscrow = await getAssociatedTokenAddress(editionMint, lotAuctionPda, true);
+    const lotEndTime = new anchor.BN(Math.floor(Date.now() / 1000) + 60);
+
+    await program.methods
+      .createAuction(listingId, minPrice, new anchor.BN(0), lotEndTime, { secondPrice: {} }, false, new anchor.BN(86_400), null, { units: 3, unitAmount: new anchor.BN(1) })
+      .accounts({
+        seller: seller.publicKey,
+        auction: lotAuctionPda,
+        config: configPda,
+        nftMint: editionMint,
+        sellerNftAccount: sellerEditionAccount,
+        nftEscrowAccount: lotEscrow,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([seller])
+      .rpc();
+
+    const lotAuction = await program.account.auction.fetch(lotAuctionPda);
+    assert.equal(lotAuction.units, 3);
+    assert.ok(lotAuction.unitAmount.eq(new anchor.BN(1)));
+    let escrowBalance = await provider.connection.getTokenAccountBalance(lotEscrow);
+    assert.equal(escrowBalance.value.uiAmount, 3);
+
+    await program.methods
+      .cancelAuction(null)
+      .accounts({
+        seller: seller.publicKey,
+        auction: lotAuctionPda,
+        nftMint: editionMint,
+        nftEscrowAccount: lotEscrow,
+        sellerNftAccount: sellerEditionAccount,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      })
+      .signers([seller])
+      .rpc();
+
+    escrowBalance = await provider.connection.getTokenAccountBalance(lotEscrow);
+    assert.equal(escrowBalance.value.uiAmount, 0);
+    const sellerBalance = await provider.connection.getTokenAccountBalance(sellerEditionAccount);
+    assert.equal(sellerBalance.value.uiAmount, 3);
+  });
+
+  it("Escrows a bundle as one lot and returns the whole set on cancel", async () => {
+    const mints: anchor.web3.PublicKey[] = [];
+    const sellerAccounts: anchor.web3.PublicKey[] = [];
+    for (let i = 0; i < 3; i++) {
+      const mint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+      const account = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, seller, mint, seller.publicKey)
+      ).address;
+      await mintTo(provider.connection, seller, mint, account, seller, 1);
+      mints.push(mint);
+      sellerAccounts.push(account);
+    }
+
+    const [bundleAuctionPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), mints[0].toBuffer(), listingId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const primaryEscrow = await getAssociatedTokenAddress(mints[0], bundleAuctionPda, true);
+    const [bundlePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("bundle"), bundleAuctionPda.toBuffer()],
+      program.programId
+    );
+
+    // The extra items' escrows are plain token accounts owned by the auction PDA
+    const extraEscrows: anchor.web3.PublicKey[] = [];
+    for (const mint of mints.slice(1)) {
+      extraEscrows.push(
+        await createAccount(provider.connection, seller, mint, bundleAuctionPda, anchor.web3.Keypair.generate())
+      );
+    }
+
+    const bundleEndTime = new anchor.BN(Math.floor(Date.now() / 1000) + 60);
+    await program.methods
+      .createAuction(listingId, minPrice, new anchor.BN(0), bundleEndTime, { firstPrice: {} }, false, new anchor.BN(86_400), null, null)
+      .accounts({
+        seller: seller.publicKey,
+        auction: bundleAuctionPda,
+        config: configPda,
+        nftMint: mints[0],
+        sellerNftAccount: sellerAccounts[0],
+        nftEscrowAccount: primaryEscrow,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        bundle: bundlePda,
+      })
+      .remainingAccounts(
+        [1, 2].flatMap((i) => [
+          { pubkey: mints[i], isSigner: false, isWritable: false },
+          { pubkey: sellerAccounts[i], isSigner: false, isWritable: true },
+          { pubkey: extraEscrows[i - 1], isSigner: false, isWritable: true },
+        ])
+      )
+      .signers([seller])
+      .rpc();
+
+    const bundle = await program.account.bundle.fetch(bundlePda);
+    assert.equal(bundle.items.length, 2);
+    assert.ok(bundle.items[1].mint.equals(mints[2]));
+    assert.equal((await program.account.auction.fetch(bundleAuctionPda)).bundleItems, 2);
+    for (const escrow of extraEscrows) {
+      assert.equal(Number((await getAccount(provider.connection, escrow)).amount), 1);
+    }
+
+    await program.methods
+      .cancelAuction(null)
+      .accounts({
+        seller: seller.publicKey,
+        auction: bundleAuctionPda,
+        nftMint: mints[0],
+        nftEscrowAccount: primaryEscrow,
+        sellerNftAccount: sellerAccounts[0],
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        bundle: bundlePda,
+      })
+      .remainingAccounts(
+        [1, 2].flatMap((i) => [
+          { pubkey: mints[i], isSigner: false, isWritable: false },
+          { pubkey: extraEscrows[i - 1], isSigner: false, isWritable: true },
+          { pubkey: sellerAccounts[i], isSigner: false, isWritable: true },
+        ])
+      )
+      .signers([seller])
+      .rpc();
+
+    for (const account of sellerAccounts) {
+      assert.equal(Number((await getAccount(provider.connection, account)).amount), 1);
+    }
+    assert.isNull(await provider.connection.getAccountInfo(bundlePda));
+  });
+
+  it("Rejects a bundle whose extra item is not an NFT", async () => {
+    const primaryMint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+    const sellerPrimaryAccount = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, seller, primaryMint, seller.publicKey)
+    ).address;
+    await mintTo(provider.connection, seller, primaryMint, sellerPrimaryAccount, seller, 1);
+
+    // Two editions of the same mint: the seller would keep one of them
+    const editionMint = await createMint(provider.connection, seller, seller.publicKey, null, 0);
+    const sellerEditionAccount = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, seller, editionMint, seller.publicKey)
+    ).address;
+    await mintTo(provider.connection, seller, editionMint, sellerEditionAccount, seller, 2);
+
+    const [bundleAuctionPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), primaryMint.toBuffer(), listingId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [bundlePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("bundle"), bundleAuctionPda.toBuffer()],
+      program.programId
+    );
+    const editionEscrow = await createAccount(
+      provider.connection,
+      seller,
+      editionMint,
+      bundleAuctionPda,
+      anchor.web3.Keypair.generate()
+    );
+
+    let error: anchor.AnchorError | undefined;
+    try {
+      await program.methods
+        .createAuction(listingId, minPrice, new anchor.BN(0), new anchor.BN(Math.floor(Date.now() / 1000) + 60), { firstPrice: {} }, false, new anchor.BN(86_400), null, null)
+        .accounts({
+          seller: seller.publicKey,
+          auction: bundleAuctionPda,
+          config: configPda,
+          nftMint: primaryMint,
+          sellerNftAccount: sellerPrimaryAccount,
+          nftEscrowAccount: await getAssociatedTokenAddress(primaryMint, bundleAuctionPda, true),
+          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          bundle: bundlePda,
+        })
+        .remainingAccounts([
+          { pubkey: editionMint, isSigner: false, isWritable: false },
+          { pubkey: sellerEditionAccount, isSigner: false, isWritable: true },
+          { pubkey: editionEscrow, isSigner: false, isWritable: true },
+        ])
+        .signers([seller])
+        .rpc();
+    } catch (err) {
+      error = err as anchor.AnchorError;
+    }
+    assert.equal(error?.error.errorCode.code, "BundleItemNotNft");
+    assert.isNull(await provider.connection.getAccountInfo(bundleAuctionPda));
+  });
+
+  it("Escrows a programmable NFT in the auction PDA's ATA and returns it on cancel", async () => {
+    // Token Metadata is cloned from mainnet (see Anchor.toml)
+    const umi = createUmi(provider.connection.rpcEndpoint)
+      .use(mplTokenMetadata())
+      .use(keypairIdentity(fromWeb3JsKeypair(seller)));
+    const mintSigner = generateSigner(umi);
+    await createProgrammableNft(umi, {
+      mint: mintSigner,
+      name: "Sealed pNFT",
+      uri: "",
+      sellerFeeBasisPoints: percentAmount(0),
+    }).sendAndConfirm(umi);
+
+    const pnftMint = toWeb3JsPublicKey(mintSigner.publicKey);
+    const sellerPnftAccount = await getAssociatedTokenAddress(pnftMint, seller.publicKey);
+    const [pnftAuctionPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), pnftMint.toBuffer(), listingId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const pnftEscrow = await getAssociatedTokenAddress(pnftMint, pnftAuctionPda, true);
+
+    const metadata = tokenMetadataPda(pnftMint);
+    const edition = tokenMetadataPda(pnftMint, Buffer.from("edition"));
+    const sellerTokenRecord = tokenMetadataPda(pnftMint, Buffer.from("token_record"), sellerPnftAccount.toBuffer());
+    const escrowTokenRecord = tokenMetadataPda(pnftMint, Buffer.from("token_record"), pnftEscrow.toBuffer());
+    const pnftAccounts = (ownerTokenRecord: anchor.web3.PublicKey, destinationTokenRecord: anchor.web3.PublicKey) => ({
+      metadata,
+      edition,
+      ownerTokenRecord,
+      destinationTokenRecord,
+      authorizationRules: null,
+      authorizationRulesProgram: null,
+      sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    });
+    // Token Metadata's TransferV1 does not fit in the default compute budget
+    const computeBudget = anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 });
+
+    const pnftEndTime = new anchor.BN(Math.floor(Date.now() / 1000) + 60);
+    await program.methods
+      .createAuction(listingId, minPrice, new anchor.BN(0), pnftEndTime, { firstPrice: {} }, false, new anchor.BN(86_400), null, null)
+      .accounts({
+        seller: seller.publicKey,
+        auction: pnftAuctionPda,
+        config: configPda,
+        nftMint: pnftMint,
+        sellerNftAccount: sellerPnftAccount,
+        nftEscrowAccount: pnftEscrow,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        pnft: pnftAccounts(sellerTokenRecord, escrowTokenRecord),
+      })
+      .preInstructions([computeBudget])
+      .signers([seller])
+      .rpc();
+
+    const pnftAuction = await program.account.auction.fetch(pnftAuctionPda);
+    assert.deepEqual(pnftAuction.assetKind, { programmableNft: {} });
+    // Token Metadata re-freezes the pNFT in the escrow ATA
+    let escrow = await getAccount(provider.connection, pnftEscrow);
+    assert.equal(Number(escrow.amount), 1);
+    assert.isTrue(escrow.isFrozen);
+
+    await program.methods
+      .cancelAuction(null)
+      .accounts({
+        seller: seller.publicKey,
+        auction: pnftAuctionPda,
+        nftMint: pnftMint,
+        nftEscrowAccount: pnftEscrow,
+        sellerNftAccount: sellerPnftAccount,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        pnft: pnftAccounts(escrowTokenRecord, sellerTokenRecord),
+      })
+      .preInstructions([computeBudget])
+      .signers([seller])
+      .rpc();
+
+    escrow = await getAccount(provider.connection, pnftEscrow);
+    assert.equal(Number(escrow.amount), 0);
+    const sellerAccount = await getAccount(provider.connection, sellerPnftAccount);
+    assert.equal(Number(sellerAccount.amount), 1);
+    assert.isTrue(sellerAccount.isFrozen);
+  });
+
+  it("Lists a compressed NFT by transferring the leaf to the auction PDA and returns it on cancel", async () => {
+    // Bubblegum and the compression programs are cloned from mainnet (see Anchor.toml)
+    const umi = createUmi(provider.connection.rpcEndpoint)
+      .use(mplBubblegum())
+      .use(keypairIdentity(fromWeb3JsKeypair(seller)));
+    const merkleTree = generateSigner(umi);
+    const maxDepth = 3;
+    await (await createTree(umi, { merkleTree, maxDepth, maxBufferSize: 8 })).sendAndConfirm(umi);
+
+    const leafMetadata: MetadataArgsArgs = {
+      name: "Sealed cNFT",
+      uri: "",
+      sellerFeeBasisPoints: 0,
+      collection: none(),
+      creators: [],
+    };
+    const { signature } = await mintV1(umi, {
+      leafOwner: umi.identity.publicKey,
+      merkleTree: merkleTree.publicKey,
+      metadata: leafMetadata,
+    }).sendAndConfirm(umi);
+    const leaf = await parseLeafFromMintV1Transaction(umi, signature);
+
+    // A fresh tree holds only this leaf, so the proof and roots can be rebuilt
+    // locally instead of asking the DAS API
+    const leafHashOwnedBy = (owner: anchor.web3.PublicKey) =>
+      publicKey(
+        hashLeaf(umi, {
+          merkleTree: merkleTree.publicKey,
+          owner: fromWeb3JsPublicKey(owner),
+          leafIndex: 0,
+          metadata: leafMetadata,
+        })
+      );
+    const sellerLeaf = leafHashOwnedBy(seller.publicKey);
+    const proof = getMerkleProof([sellerLeaf], maxDepth, sellerLeaf).map((node) => ({
+      pubkey: toWeb3JsPublicKey(node),
+      isSigner: false,
+      isWritable: false,
+    }));
+    const currentRoot = async () =>
+      getCurrentRoot((await fetchMerkleTree(umi, merkleTree.publicKey)).tree);
+
+    const compressed = {
+      assetId: toWeb3JsPublicKey(leaf.id),
+      merkleTree: toWeb3JsPublicKey(merkleTree.publicKey),
+      dataHash: Array.from(leaf.dataHash),
+      creatorHash: Array.from(leaf.creatorHash),
+      nonce: new anchor.BN(leaf.nonce.toString()),
+      index: 0,
+    };
+    const [cnftAuctionPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), compressed.assetId.toBuffer(), listingId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const cnftAccounts = {
+      treeAuthority: toWeb3JsPublicKey(findTreeConfigPda(umi, { merkleTree: merkleTree.publicKey })[0]),
+      merkleTree: compressed.merkleTree,
+      logWrapper: toWeb3JsPublicKey(SPL_NOOP_PROGRAM_ID),
+      compressionProgram: toWeb3JsPublicKey(SPL_ACCOUNT_COMPRESSION_PROGRAM_ID),
+      bubblegumProgram: toWeb3JsPublicKey(MPL_BUBBLEGUM_PROGRAM_ID),
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+
+    const cnftEndTime = new anchor.BN(Math.floor(Date.now() / 1000) + 60);
+    await program.methods
+      .createCompressedAuction(
+        listingId,
+        compressed,
+        Array.from(publicKeyBytes(await currentRoot())),
+        minPrice,
+        new anchor.BN(0),
+        cnftEndTime,
+        { firstPrice: {} },
+        false,
+        new anchor.BN(86_400),
+        null
+      )
+      .accounts({
+        seller: seller.publicKey,
+        auction: cnftAuctionPda,
+        config: configPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        cnft: cnftAccounts,
+      })
+      .remainingAccounts(proof)
+      .signers([seller])
+      .rpc();
+
+    const cnftAuction = await program.account.auction.fetch(cnftAuctionPda);
+    assert.deepEqual(cnftAuction.assetKind, { compressed: {} });
+    assert.ok(cnftAuction.nftMint.equals(compressed.assetId));
+    // The tree now commits to the same leaf owned by the auction PDA
+    assert.equal(await currentRoot(), getMerkleRoot([leafHashOwnedBy(cnftAuctionPda)], maxDepth));
+
+    await program.methods
+      .cancelAuction(Array.from(publicKeyBytes(await currentRoot())))
+      .accounts({
+        seller: seller.publicKey,
+        auction: cnftAuctionPda,
+        cnft: cnftAccounts,
+      })
+      .remainingAccounts(proof)
+      .signers([seller])
+      .rpc();
+
+    assert.equal(await currentRoot(), getMerkleRoot([sellerLeaf], maxDepth));
+    assert.isNull(await provider.connection.getAccountInfo(cnftAuctionPda));
+  });
+
+  it("Lists a Core asset by making the auction PDA its owner and returns it on cancel", async () => {
+    // Core is cloned from mainnet (see Anchor.toml)
+    const umi = createUmi(provider.connection.rpcEndpoint)
+      .use(mplCore())
+      .use(keypairIdentity(fromWeb3JsKeypair(seller)));
+    const assetSigner = generateSigner(umi);
+    await createCoreAsset(umi, { asset: assetSigner, name: "Sealed Core asset", uri: "" }).sendAndConfirm(umi);
+
+    const asset = toWeb3JsPublicKey(assetSigner.publicKey);
+    const [coreAuctionPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), asset.toBuffer(), listingId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const coreProgram = toWeb3JsPublicKey(MPL_CORE_PROGRAM_ID);
+
+    const coreEndTime = new anchor.BN(Math.floor(Date.now() / 1000) + 60);
+    await program.methods
+      .createCoreAuction(listingId, minPrice, new anchor.BN(0), coreEndTime, { firstPrice: {} }, false, new anchor.BN(86_400), null)
+      .accounts({
+        seller: seller.publicKey,
+        auction: coreAuctionPda,
+        config: configPda,
+        asset,
+        collection: null,
+        coreProgram,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([seller])
+      .rpc();
+
+    const coreAuction = await program.account.auction.fetch(coreAuctionPda);
+    assert.deepEqual(coreAuction.assetKind, { core: {} });
+    assert.ok(coreAuction.nftMint.equals(asset));
+    assert.equal((await fetchAsset(umi, assetSigner.publicKey)).owner, fromWeb3JsPublicKey(coreAuctionPda));
+
+    await program.methods
+      .cancelAuction(null)
+      .accounts({
+        seller: seller.publicKey,
+        auction: coreAuctionPda,
+        core: {
+          asset,
+          collection: null,
+          coreProgram,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        },
+      })
+      .signers([seller])
+      .rpc();
+
+    assert.equal((await fetchAsset(umi, assetSigner.publicKey)).owner, fromWeb3JsPublicKey(seller.publicKey));
+    assert.isNull(await provider.connection.getAccountInfo(coreAuctionPda));
+  });
+
   it("Bidder 1 locks funds (Winner candidate)", async () => {
     const bidAmount = new anchor.BN(2_000_000_000); // 2 SOL
 
@@ -141,12 +676,100 @@ describe("sealed_auction", () => {
       .rpc();
   });
 
+  it("Bidder 2 tops up collateral", async () => {
+    const topUp = new anchor.BN(250_000_000); // 0.25 SOL
+
+    const [bidEscrowPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("bid_escrow"), auctionPda.toBuffer(), bidder2.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const before = await program.account.bidEscrow.fetch(bidEscrowPda);
+
+    await program.methods
+      .increaseBidCollateral(topUp)
+      .accounts({
+        bidder: bidder2.publicKey,
+        auction: auctionPda,
+        bidEscrow: bidEscrowPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([bidder2])
+      .rpc();
+
+    const escrowAccount = await program.account.bidEscrow.fetch(bidEscrowPda);
+    assert.ok(escrowAccount.maxLockedAmount.eq(new anchor.BN(750_000_000)));
+    assert.ok(escrowAccount.lockedSlot.eq(before.lockedSlot));
+
+    // A top-up is not a new bid
+    const auctionAccount = await program.account.auction.fetch(auctionPda);
+    assert.equal(auctionAccount.bidCount.toNumber(), 2);
+  });
+
+  it("Bidder 3 locks funds and withdraws before the end", async () => {
+    const bidAmount = new anchor.BN(1_000_000_000); // 1 SOL
+
+    const [bidEscrowPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("bid_escrow"), auctionPda.toBuffer(), bidder3.publicKey.toBuffer()],
+      program.programId
+    );
+    const [bidWithdrawalPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("bid_withdrawal"), auctionPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .lockBidFunds(bidAmount)
+      .accounts({
+        bidder: bidder3.publicKey,
+        auction: auctionPda,
+        bidEscrow: bidEscrowPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([bidder3])
+      .rpc();
+
+    // Withdrawals are only allowed from the slot after the escrow was locked
+    const lockedSlot = (await program.account.bidEscrow.fetch(bidEscrowPda)).lockedSlot.toNumber();
+    while ((await provider.connection.getSlot()) <= lockedSlot) {
+      await new Promise(resolve => setTimeout(resolve, 200));
+    }
+
+    await program.methods
+      .withdrawBid()
+      .accounts({
+        bidder: bidder3.publicKey,
+        auction: auctionPda,
+        bidEscrow: bidEscrowPda,
+        bidWithdrawal: bidWithdrawalPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([bidder3])
+      .rpc();
+
+    // Escrow is closed and the receipt records the bidder for arcium_program's retract_bid
+    assert.isNull(await provider.connection.getAccountInfo(bidEscrowPda));
+    const receipt = await program.account.bidWithdrawal.fetch(bidWithdrawalPda);
+    assert.ok(receipt.bidder.equals(bidder3.publicKey));
+
+    const auctionAccount = await program.account.auction.fetch(auctionPda);
+    assert.equal(auctionAccount.bidCount.toNumber(), 2);
+    assert.equal(auctionAccount.withdrawalCount.toNumber(), 1);
+  });
+
   it("Settles Auction (Winner = Bidder 1)", async () => {
     // Wait for end time
     console.log("Waiting for auction to end...");
-    await new Promise(resolve => setTimeout(resolve, 6000));
+    await new Promise(resolve => setTimeout(resolve, 11000));
 
-    const winningAmount = new anchor.BN(1_500_000_000); // 1.5 SOL (Bidder 1 bids 1.5, locked 2)
+    // tests/fixtures/auction-result.json: bidder 1 won with 1.5 SOL (locked 2)
+    const [auctionResultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_result"), auctionPda.toBuffer()],
+      new anchor.web3.PublicKey("2excUVgCNGZDN4yHGBbxBg4ptNYTH1nyqnJ5HArAG6wC")
+    );
+    const result = await program.account.auctionResult.fetch(auctionResultPda);
+    assert.ok(result.revealed);
+    assert.ok(result.winner.equals(bidder1.publicKey));
 
     // Create ATAs for winner
     const winnerNftAccount = await getOrCreateAssociatedTokenAccount(
@@ -161,22 +784,19 @@ describe("sealed_auction", () => {
       program.programId
     );
 
-    // Dummy proof
-    const proof = {
-      messageHash: Array(32).fill(0),
-      signature: Array(64).fill(0),
-    };
-
     const initialSellerBalance = await provider.connection.getBalance(seller.publicKey);
 
     await program.methods
-      .settleAuction(bidder1.publicKey, winningAmount, proof)
-      .accounts({
+      .settleAuction(null)
+      .accountsPartial({
+        payer: provider.wallet.publicKey,
         seller: seller.publicKey,
         winner: bidder1.publicKey,
         auction: auctionPda,
+        auctionResult: auctionResultPda,
         winnerBidEscrow: winnerBidEscrow,
-        nftEscrowAccount: nftEscrow.publicKey,
+        treasury: treasury.publicKey,
+        nftEscrowAccount: nftEscrow,
         winnerNftAccount: winnerNftAccount.address,
         nftMint: nftMint,
         tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
@@ -186,11 +806,12 @@ describe("sealed_auction", () => {
 
     // Verify Balances
     const finalSellerBalance = await provider.connection.getBalance(seller.publicKey);
-    // Seller received 1.5 SOL (approx)
+    // Seller received 1.5 SOL minus the 2.5% protocol fee
+    const fee = 1_500_000_000 * feeBps / 10_000;
     const diff = finalSellerBalance - initialSellerBalance;
-    assert.ok(diff >= 1_500_000_000 - 1000000); // Minus transaction fees noise if signer? Seller didn't sign, so exact amount?
     // Seller is NOT signer in settle_auction (UncheckedAccount). So exact amount.
-    assert.equal(diff, 1_500_000_000);
+    assert.equal(diff, 1_500_000_000 - fee);
+    assert.equal(await provider.connection.getBalance(treasury.publicKey), fee);
 
     // Verify NFT ownership
     const winnerNftBalance = await provider.connection.getTokenAccountBalance(winnerNftAccount.address);
@@ -209,6 +830,12 @@ describe("sealed_auction", () => {
       program.programId
     );
 
+    // Checked for defaulters before refunding
+    const [auctionResultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_result"), auctionPda.toBuffer()],
+      new anchor.web3.PublicKey("2excUVgCNGZDN4yHGBbxBg4ptNYTH1nyqnJ5HArAG6wC")
+    );
+
     const initialBidder2Balance = await provider.connection.getBalance(bidder2.publicKey);
 
     await program.methods
@@ -217,6 +844,7 @@ describe("sealed_auction", () => {
         bidder: bidder2.publicKey,
         bidEscrow: bidEscrowPda,
         auction: auctionPda,
+        auctionResult: auctionResultPda,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([bidder2]) // Bidder doesn't need to sign? RefundLoser `bidder` is UncheckedAccount unless used as payer?
@@ -234,6 +862,39 @@ describe("sealed_auction", () => {
     assert.ok(finalBidder2Balance > initialBidder2Balance);
   });
 
+  it("Batch refund skips escrows that are already closed", async () => {
+    const [auctionResultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_result"), auctionPda.toBuffer()],
+      new anchor.web3.PublicKey("2excUVgCNGZDN4yHGBbxBg4ptNYTH1nyqnJ5HArAG6wC")
+    );
+
+    // The winner's escrow was closed by settlement and bidder 2's by refund_loser,
+    // so a crank racing them must still succeed
+    const remainingAccounts = [bidder1, bidder2].flatMap((bidder) => {
+      const [bidEscrowPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("bid_escrow"), auctionPda.toBuffer(), bidder.publicKey.toBuffer()],
+        program.programId
+      );
+      return [
+        { pubkey: bidder.publicKey, isSigner: false, isWritable: true },
+        { pubkey: bidEscrowPda, isSigner: false, isWritable: true },
+      ];
+    });
+
+    await program.methods
+      .refundLosersBatch()
+      .accounts({
+        auction: auctionPda,
+        auctionResult: auctionResultPda,
+      })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+
+    for (const { pubkey } of remainingAccounts.filter((_, i) => i % 2 === 1)) {
+      assert.isNull(await provider.connection.getAccountInfo(pubkey));
+    }
+  });
+
   async function confirmTx(txSignature: string) {
     const latestBlockhash = await provider.connection.getLatestBlockhash();
     await provider.connection.confirmTransaction({
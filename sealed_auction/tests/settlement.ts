@@ -0,0 +1,844 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { fromWorkspace, LiteSVMProvider } from "anchor-litesvm";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  MINT_SIZE,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+  unpackAccount,
+} from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { assert } from "chai";
+import * as fs from "fs";
+import { SealedAuction } from "../target/types/sealed_auction";
+
+const ARCIUM_PROGRAM_ID = new PublicKey("2excUVgCNGZDN4yHGBbxBg4ptNYTH1nyqnJ5HArAG6wC");
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Settlement paths run in LiteSVM rather than against the test validator:
+/// revealed AuctionResults are written straight into arcium_program's PDA
+/// and the clock is moved past end times instead of waited for.
+describe("settlement", () => {
+  const svm = fromWorkspace(".");
+  const provider = new LiteSVMProvider(svm);
+  const program = new Program<SealedAuction>(
+    JSON.parse(fs.readFileSync("target/idl/sealed_auction.json").toString()),
+    provider
+  );
+  const payer = provider.wallet.payer;
+  const treasury = Keypair.generate();
+  const feeBps = 250; // 2.5%
+  const slashBps = 1_000; // 10%
+
+  const [configPda, configBump] = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
+
+  const start = 1_700_000_000;
+  let now = start;
+
+  before(async () => {
+    svm.airdrop(payer.publicKey, BigInt(1_000 * LAMPORTS_PER_SOL));
+    svm.airdrop(treasury.publicKey, BigInt(LAMPORTS_PER_SOL));
+    setTime(start);
+    // initialize_config's upgrade-authority check is covered by sealed_auction.ts
+    await setConfig(feeBps);
+  });
+
+  it("Settles an SPL-paid auction in the payment token", async () => {
+    const usdc = await createMint(6);
+    const listing = await listNft({ paymentMint: usdc });
+    const { seller, auction, nftMint } = listing;
+
+    const bidder = fundedKeypair();
+    const bidderUsdc = await tokenAccount(usdc, bidder.publicKey);
+    await mintTo(usdc, bidderUsdc, 500_000_000);
+    await lockBid(listing, bidder, 300_000_000);
+    assert.equal(tokenBalance(escrowVaultPda(auction, bidder.publicKey)), 300_000_000);
+    assert.equal(tokenBalance(bidderUsdc), 200_000_000);
+
+    await setResult(auction, { winner: bidder.publicKey, winningAmount: 250_000_000 });
+    setTime(listing.endTime + 1);
+    await settle(listing, bidder.publicKey);
+
+    // The clearing price is split between seller and treasury, the rest of
+    // the locked tokens go back to the winner and the vault is closed
+    const fee = (250_000_000 * feeBps) / 10_000;
+    assert.equal(tokenBalance(ata(usdc, seller.publicKey)), 250_000_000 - fee);
+    assert.equal(tokenBalance(ata(usdc, treasury.publicKey)), fee);
+    assert.equal(tokenBalance(bidderUsdc), 250_000_000);
+    assert.isNull(svm.getAccount(escrowVaultPda(auction, bidder.publicKey)));
+    assert.equal(tokenBalance(ata(nftMint, bidder.publicKey)), 1);
+    assert.ok((await program.account.auction.fetch(auction)).settled);
+  });
+
+  it("Auctions a Token-2022 NFT for Token-2022 payment tokens", async () => {
+    const payment = await createMint(6, payer.publicKey, TOKEN_2022_PROGRAM_ID);
+    const listing = await listNft({
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      paymentMint: payment,
+      paymentTokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+    const { seller, auction, nftMint, nftEscrow } = listing;
+    assert.equal(tokenBalance(nftEscrow), 1);
+
+    const bidder = fundedKeypair();
+    const bidderPayment = await tokenAccount(payment, bidder.publicKey, TOKEN_2022_PROGRAM_ID);
+    await mintTo(payment, bidderPayment, 100_000_000, payer, TOKEN_2022_PROGRAM_ID);
+    await lockBid(listing, bidder, 100_000_000);
+
+    await setResult(auction, { winner: bidder.publicKey, winningAmount: 80_000_000 });
+    setTime(listing.endTime + 1);
+    await settle(listing, bidder.publicKey);
+
+    const fee = (80_000_000 * feeBps) / 10_000;
+    assert.equal(tokenBalance(ata(payment, seller.publicKey, TOKEN_2022_PROGRAM_ID)), 80_000_000 - fee);
+    assert.equal(tokenBalance(ata(payment, treasury.publicKey, TOKEN_2022_PROGRAM_ID)), fee);
+    assert.equal(tokenBalance(bidderPayment), 20_000_000);
+    assert.equal(tokenBalance(ata(nftMint, bidder.publicKey, TOKEN_2022_PROGRAM_ID)), 1);
+    assert.equal(tokenBalance(nftEscrow), 0);
+  });
+
+  it("Rejects a plaintext min_price on a hidden-reserve listing", async () => {
+    await expectError(listNft({ hiddenReserve: true, minPrice: 1_000_000 }), "InvalidMinPrice");
+  });
+
+  it("Charges the MPC-floored second price of a hidden-reserve auction", async () => {
+    // min_price stays 0; the reserve lives only in arcium_program's ciphertext
+    const listing = await listNft({ auctionType: { secondPrice: {} }, hiddenReserve: true, minPrice: 0 });
+    const { seller, auction } = listing;
+    assert.equal((await program.account.auction.fetch(auction)).minPrice.toNumber(), 0);
+
+    const bidder = fundedKeypair();
+    await lockBid(listing, bidder, LAMPORTS_PER_SOL);
+
+    // The only valid bid is priced at the 0.4 SOL reserve the MPC floored it to
+    await setResult(auction, {
+      winner: bidder.publicKey,
+      winningAmount: 0.6 * LAMPORTS_PER_SOL,
+      secondAmount: 0.4 * LAMPORTS_PER_SOL,
+    });
+    setTime(listing.endTime + 1);
+
+    const sellerBefore = Number(svm.getBalance(seller.publicKey));
+    const bidderBefore = Number(svm.getBalance(bidder.publicKey));
+    await settle(listing, bidder.publicKey);
+
+    const price = 0.4 * LAMPORTS_PER_SOL;
+    assert.equal(Number(svm.getBalance(seller.publicKey)) - sellerBefore, price - (price * feeBps) / 10_000);
+    // The winner gets the unspent collateral back along with the escrow's rent
+    assert.isAbove(Number(svm.getBalance(bidder.publicKey)) - bidderBefore, LAMPORTS_PER_SOL - price);
+  });
+
+  it("Unwinds an auction Arcium never resolved once the grace period ends", async () => {
+    const listing = await listNft();
+    const { seller, auction, nftMint } = listing;
+    const sellerNftAccount = ata(nftMint, seller.publicKey);
+
+    const bidder = fundedKeypair();
+    await lockBid(listing, bidder, LAMPORTS_PER_SOL);
+
+    // reveal_winner was never queued, so no AuctionResult exists
+    const expire = () =>
+      program.methods
+        .expireUnresolved(null)
+        .accountsPartial({
+          payer: payer.publicKey,
+          seller: seller.publicKey,
+          auction,
+          auctionResult: auctionResultPda(auction),
+          nftMint,
+          nftEscrowAccount: listing.nftEscrow,
+          sellerNftAccount,
+          tokenProgram: listing.tokenProgram,
+        })
+        .rpc();
+
+    setTime(listing.endTime + 86_400);
+    await expectError(expire(), "GracePeriodNotElapsed");
+
+    setTime(listing.endTime + 86_400 + 1);
+    await expire();
+    assert.equal(tokenBalance(sellerNftAccount), 1);
+    const expired = await program.account.auction.fetch(auction);
+    assert.ok(expired.expired);
+    assert.ok(expired.settled);
+
+    // Refunds of an expired auction take no AuctionResult
+    const bidderBefore = Number(svm.getBalance(bidder.publicKey));
+    await refund(listing, bidder.publicKey, { auctionResult: null });
+    assert.isAtLeast(Number(svm.getBalance(bidder.publicKey)) - bidderBefore, LAMPORTS_PER_SOL);
+    assert.isNull(svm.getAccount(bidEscrowPda(auction, bidder.publicKey)));
+  });
+
+  it("Closes withdrawals once more escrows were locked than arcium_program ranks", async () => {
+    const listing = await listNft();
+    const bidders = Array.from({ length: 9 }, () => fundedKeypair());
+    for (const bidder of bidders.slice(0, 8)) {
+      await lockBid(listing, bidder, LAMPORTS_PER_SOL);
+    }
+    svm.warpToSlot(svm.getClock().slot + 1n);
+
+    // Eight escrows all fit in the ranking, withdrawn ones included
+    await withdrawBid(listing, bidders[0]);
+    await lockBid(listing, bidders[8], LAMPORTS_PER_SOL);
+    svm.warpToSlot(svm.getClock().slot + 1n);
+
+    // A ninth may have pushed a bid out, so nothing can be retracted any more
+    await expectError(withdrawBid(listing, bidders[1]), "WithdrawalsClosed");
+    const auction = await program.account.auction.fetch(listing.auction);
+    assert.equal(auction.bidCount.toNumber(), 8);
+    assert.equal(auction.withdrawalCount.toNumber(), 1);
+  });
+
+  it("Opens bidding at start_time", async () => {
+    const startTime = now + 50;
+    const listing = await listNft({ startTime });
+    const bidder = fundedKeypair();
+
+    await expectError(lockBid(listing, bidder, LAMPORTS_PER_SOL), "AuctionNotStarted");
+
+    setTime(startTime);
+    await lockBid(listing, bidder, LAMPORTS_PER_SOL);
+    assert.equal((await program.account.auction.fetch(listing.auction)).bidCount.toNumber(), 1);
+  });
+
+  it("Extends end_time for locks in the soft-close window, up to the cap", async () => {
+    const endTime = now + 100;
+    const maxEndTime = endTime + 90;
+    const listing = await listNft({ softClose: { window: 30, extension: 60, maxEndTime } });
+    assert.equal(listing.endTime, endTime);
+    const endTimeNow = async () => (await program.account.auction.fetch(listing.auction)).endTime.toNumber();
+
+    // Just before the window nothing changes
+    setTime(endTime - 31);
+    await lockBid(listing, fundedKeypair(), LAMPORTS_PER_SOL);
+    assert.equal(await endTimeNow(), endTime);
+
+    setTime(endTime - 30);
+    await lockBid(listing, fundedKeypair(), LAMPORTS_PER_SOL);
+    assert.equal(await endTimeNow(), endTime + 60);
+
+    // A second extension would pass max_end_time, so it stops there
+    setTime(endTime + 50);
+    await lockBid(listing, fundedKeypair(), LAMPORTS_PER_SOL);
+    assert.equal(await endTimeNow(), maxEndTime);
+
+    setTime(maxEndTime);
+    await expectError(lockBid(listing, fundedKeypair(), LAMPORTS_PER_SOL), "AuctionEnded");
+  });
+
+  it("Settles fee-free auctions without treasury accounts", async () => {
+    // The fee is snapshotted at creation, so only these listings are fee-free
+    await setConfig(0);
+    const solListing = await listNft();
+    const usdc = await createMint(6);
+    const splListing = await listNft({ paymentMint: usdc });
+    await setConfig(feeBps);
+
+    const solBidder = fundedKeypair();
+    await lockBid(solListing, solBidder, LAMPORTS_PER_SOL);
+    const splBidder = fundedKeypair();
+    await mintTo(usdc, await tokenAccount(usdc, splBidder.publicKey), 100_000_000);
+    await lockBid(splListing, splBidder, 100_000_000);
+
+    await setResult(solListing.auction, { winner: solBidder.publicKey, winningAmount: 0.5 * LAMPORTS_PER_SOL });
+    await setResult(splListing.auction, { winner: splBidder.publicKey, winningAmount: 100_000_000 });
+    setTime(splListing.endTime + 1);
+
+    const sellerBefore = Number(svm.getBalance(solListing.seller.publicKey));
+    await settle(solListing, solBidder.publicKey, { treasury: null });
+    assert.equal(Number(svm.getBalance(solListing.seller.publicKey)) - sellerBefore, 0.5 * LAMPORTS_PER_SOL);
+
+    await settle(splListing, splBidder.publicKey, { treasury: null, treasuryPaymentAccount: null });
+    assert.equal(tokenBalance(ata(usdc, splListing.seller.publicKey)), 100_000_000);
+  });
+
+  it("Leaves a SOL royalty the creator wallet cannot hold with the seller", async () => {
+    const listing = await listNft();
+    const { seller, auction, nftMint } = listing;
+    const funded = fundedKeypair().publicKey;
+    const missing = Keypair.generate().publicKey;
+    setMetadata(nftMint, 500, [
+      { address: funded, share: 50 },
+      { address: missing, share: 50 },
+    ]);
+
+    const bidder = fundedKeypair();
+    await lockBid(listing, bidder, LAMPORTS_PER_SOL);
+    const price = 10_000_000;
+    await setResult(auction, { winner: bidder.publicKey, winningAmount: price });
+    setTime(listing.endTime + 1);
+
+    // Each creator is owed 250_000 lamports, too little to open an account
+    const share = (price * 500) / 10_000 / 2;
+    assert.isBelow(share, Number(svm.minimumBalanceForRentExemption(0n)));
+
+    const sellerBefore = Number(svm.getBalance(seller.publicKey));
+    const fundedBefore = Number(svm.getBalance(funded));
+    await settle(listing, bidder.publicKey, {}, [funded, missing]);
+
+    assert.equal(Number(svm.getBalance(funded)) - fundedBefore, share);
+    assert.isNull(svm.getAccount(missing));
+    const fee = (price * feeBps) / 10_000;
+    assert.equal(Number(svm.getBalance(seller.publicKey)) - sellerBefore, price - fee - share);
+  });
+
+  it("Leaves an SPL royalty without a creator token account with the seller", async () => {
+    const usdc = await createMint(6);
+    const listing = await listNft({ paymentMint: usdc });
+    const { seller, auction, nftMint } = listing;
+    const withAccount = Keypair.generate().publicKey;
+    const withoutAccount = Keypair.generate().publicKey;
+    await tokenAccount(usdc, withAccount);
+    setMetadata(nftMint, 1_000, [
+      { address: withAccount, share: 60 },
+      { address: withoutAccount, share: 40 },
+    ]);
+
+    const bidder = fundedKeypair();
+    await mintTo(usdc, await tokenAccount(usdc, bidder.publicKey), 100_000_000);
+    await lockBid(listing, bidder, 100_000_000);
+    await setResult(auction, { winner: bidder.publicKey, winningAmount: 100_000_000 });
+    setTime(listing.endTime + 1);
+
+    // Creator token accounts are their ATAs, passed even when they don't exist
+    await settle(listing, bidder.publicKey, {}, [ata(usdc, withAccount), ata(usdc, withoutAccount)]);
+
+    const royalty = 10_000_000;
+    const fee = (100_000_000 * feeBps) / 10_000;
+    assert.equal(tokenBalance(ata(usdc, withAccount)), royalty * 0.6);
+    assert.isNull(svm.getAccount(ata(usdc, withoutAccount)));
+    assert.equal(tokenBalance(ata(usdc, seller.publicKey)), 100_000_000 - fee - royalty * 0.6);
+  });
+
+  it("Batch-refunds a SOL loser but skips the winner and an unslashed defaulter", async () => {
+    const listing = await listNft();
+    const { auction } = listing;
+    const [winner, loser, defaulter] = [fundedKeypair(), fundedKeypair(), fundedKeypair()];
+    for (const bidder of [winner, loser, defaulter]) {
+      await lockBid(listing, bidder, 300_000_000);
+    }
+    await setResult(auction, { winner: winner.publicKey, winningAmount: 300_000_000, defaulters: [defaulter.publicKey] });
+    setTime(listing.endTime + 1);
+    // Keep the winner's escrow open, as if settle_auction had not closed it yet
+    await markSettled(auction);
+
+    const loserEscrow = bidEscrowPda(auction, loser.publicKey);
+    const refunded = Number(svm.getBalance(loserEscrow));
+    const loserBefore = Number(svm.getBalance(loser.publicKey));
+    await refundBatch(
+      listing,
+      [winner, loser, defaulter].map((bidder) => refundGroup(listing, bidder.publicKey))
+    );
+
+    assert.equal(Number(svm.getBalance(loser.publicKey)) - loserBefore, refunded);
+    assert.isNull(svm.getAccount(loserEscrow));
+    assert.isNotNull(svm.getAccount(bidEscrowPda(auction, winner.publicKey)));
+    assert.isNotNull(svm.getAccount(bidEscrowPda(auction, defaulter.publicKey)));
+  });
+
+  it("Batch-refunds an SPL loser's tokens and closes their vault", async () => {
+    const usdc = await createMint(6);
+    const listing = await listNft({ paymentMint: usdc });
+    const { auction } = listing;
+    const [winner, loser] = [fundedKeypair(), fundedKeypair()];
+    for (const bidder of [winner, loser]) {
+      await mintTo(usdc, await tokenAccount(usdc, bidder.publicKey), 100_000_000);
+      await lockBid(listing, bidder, 100_000_000);
+    }
+    await setResult(auction, { winner: winner.publicKey, winningAmount: 100_000_000 });
+    setTime(listing.endTime + 1);
+    await settle(listing, winner.publicKey);
+
+    // The winner's escrow is already closed by settlement and is skipped
+    await refundBatch(
+      listing,
+      [winner, loser].map((bidder) => refundGroup(listing, bidder.publicKey))
+    );
+
+    assert.equal(tokenBalance(ata(usdc, loser.publicKey)), 100_000_000);
+    assert.isNull(svm.getAccount(escrowVaultPda(auction, loser.publicKey)));
+    assert.isNull(svm.getAccount(bidEscrowPda(auction, loser.publicKey)));
+  });
+
+  it("Fails the whole batch on a wrong bid_vault or destination token account", async () => {
+    const usdc = await createMint(6);
+    const listing = await listNft({ paymentMint: usdc });
+    const { auction } = listing;
+    const [winner, loser, other] = [fundedKeypair(), fundedKeypair(), fundedKeypair()];
+    for (const bidder of [winner, loser, other]) {
+      await mintTo(usdc, await tokenAccount(usdc, bidder.publicKey), 100_000_000);
+      await lockBid(listing, bidder, 100_000_000);
+    }
+    await setResult(auction, { winner: winner.publicKey, winningAmount: 100_000_000 });
+    setTime(listing.endTime + 1);
+    await settle(listing, winner.publicKey);
+
+    const [loserKey, loserEscrow] = refundGroup(listing, loser.publicKey);
+    const otherGroup = refundGroup(listing, other.publicKey);
+    await expectError(
+      refundBatch(listing, [otherGroup, [loserKey, loserEscrow, escrowVaultPda(auction, other.publicKey), ata(usdc, loser.publicKey)]]),
+      "InvalidRefundBatch"
+    );
+    await expectError(
+      refundBatch(listing, [otherGroup, [loserKey, loserEscrow, escrowVaultPda(auction, loser.publicKey), ata(usdc, other.publicKey)]]),
+      "InvalidRefundBatch"
+    );
+
+    // Neither batch refunded anyone, the valid group included
+    for (const bidder of [loser, other]) {
+      assert.equal(tokenBalance(escrowVaultPda(auction, bidder.publicKey)), 100_000_000);
+      assert.equal(tokenBalance(ata(usdc, bidder.publicKey)), 0);
+    }
+  });
+
+  // --- Listing, bidding and settling ---
+
+  type Listing = {
+    seller: Keypair;
+    auction: PublicKey;
+    nftMint: PublicKey;
+    nftEscrow: PublicKey;
+    endTime: number;
+    /// Token program of the NFT mint
+    tokenProgram: PublicKey;
+    /// None for SOL auctions
+    paymentMint: PublicKey | null;
+    paymentTokenProgram: PublicKey;
+  };
+
+  /// Lists a fresh NFT from a fresh seller, first-price with a 0.001 SOL
+  /// (or 1 token) minimum, open now and ending 100 seconds after the start
+  /// unless overridden
+  async function listNft(
+    options: {
+      auctionType?: object;
+      minPrice?: number;
+      startTime?: number;
+      hiddenReserve?: boolean;
+      softClose?: { window: number; extension: number; maxEndTime: number };
+      paymentMint?: PublicKey;
+      tokenProgram?: PublicKey;
+      paymentTokenProgram?: PublicKey;
+    } = {}
+  ): Promise<Listing> {
+    const tokenProgram = options.tokenProgram ?? TOKEN_PROGRAM_ID;
+    const seller = fundedKeypair();
+    const nftMint = await createMint(0, seller.publicKey, tokenProgram);
+    const sellerNftAccount = await tokenAccount(nftMint, seller.publicKey, tokenProgram);
+    await mintTo(nftMint, sellerNftAccount, 1, seller, tokenProgram);
+
+    const listingId = new anchor.BN(1);
+    const [auction] = PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), nftMint.toBuffer(), listingId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const nftEscrow = ata(nftMint, auction, tokenProgram);
+    const startTime = options.startTime ?? 0;
+    const endTime = Math.max(startTime, now) + 100;
+
+    await program.methods
+      .createAuction(
+        listingId,
+        new anchor.BN(options.minPrice ?? 1_000_000),
+        new anchor.BN(startTime),
+        new anchor.BN(endTime),
+        options.auctionType ?? { firstPrice: {} },
+        options.hiddenReserve ?? false,
+        new anchor.BN(86_400),
+        options.softClose
+          ? {
+              window: new anchor.BN(options.softClose.window),
+              extension: new anchor.BN(options.softClose.extension),
+              maxEndTime: new anchor.BN(options.softClose.maxEndTime),
+            }
+          : null,
+        null
+      )
+      .accountsPartial({
+        seller: seller.publicKey,
+        auction,
+        config: configPda,
+        nftMint,
+        paymentMint: options.paymentMint ?? null,
+        sellerNftAccount,
+        nftEscrowAccount: nftEscrow,
+        tokenProgram,
+      })
+      .signers([seller])
+      .rpc();
+
+    return {
+      seller,
+      auction,
+      nftMint,
+      nftEscrow,
+      endTime,
+      tokenProgram,
+      paymentMint: options.paymentMint ?? null,
+      paymentTokenProgram: options.paymentTokenProgram ?? TOKEN_PROGRAM_ID,
+    };
+  }
+
+  /// Locks `amount` lamports, or payment tokens from the bidder's ATA
+  async function lockBid(listing: Listing, bidder: Keypair, amount: number): Promise<void> {
+    const { auction, paymentMint, paymentTokenProgram } = listing;
+    await program.methods
+      .lockBidFunds(new anchor.BN(amount))
+      .accountsPartial({
+        bidder: bidder.publicKey,
+        auction,
+        bidEscrow: bidEscrowPda(auction, bidder.publicKey),
+        paymentMint,
+        bidderPaymentAccount: paymentMint ? ata(paymentMint, bidder.publicKey, paymentTokenProgram) : null,
+        escrowVault: paymentMint ? escrowVaultPda(auction, bidder.publicKey) : null,
+        paymentTokenProgram: paymentMint ? paymentTokenProgram : null,
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  async function withdrawBid(listing: Listing, bidder: Keypair): Promise<void> {
+    const { auction, paymentMint, paymentTokenProgram } = listing;
+    const { withdrawalCount } = await program.account.auction.fetch(auction);
+    const [bidWithdrawal] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bid_withdrawal"), auction.toBuffer(), withdrawalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .withdrawBid()
+      .accountsPartial({
+        bidder: bidder.publicKey,
+        auction,
+        bidEscrow: bidEscrowPda(auction, bidder.publicKey),
+        bidWithdrawal,
+        paymentMint,
+        escrowVault: paymentMint ? escrowVaultPda(auction, bidder.publicKey) : null,
+        bidderPaymentAccount: paymentMint ? ata(paymentMint, bidder.publicKey, paymentTokenProgram) : null,
+        paymentTokenProgram: paymentMint ? paymentTokenProgram : null,
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  /// Settles `listing` to `winner`, creating the NFT and payment ATAs it
+  /// pays into; `overrides` replaces any of the accounts. `creatorAccounts`
+  /// follow in metadata order.
+  async function settle(
+    listing: Listing,
+    winner: PublicKey,
+    overrides: object = {},
+    creatorAccounts: PublicKey[] = []
+  ): Promise<void> {
+    const { seller, auction, nftMint, paymentMint, paymentTokenProgram } = listing;
+    const payment = paymentMint && {
+      paymentMint,
+      winnerEscrowVault: escrowVaultPda(auction, winner),
+      sellerPaymentAccount: await tokenAccount(paymentMint, seller.publicKey, paymentTokenProgram),
+      winnerPaymentAccount: await tokenAccount(paymentMint, winner, paymentTokenProgram),
+      treasuryPaymentAccount: await tokenAccount(paymentMint, treasury.publicKey, paymentTokenProgram),
+      paymentTokenProgram,
+    };
+
+    await program.methods
+      .settleAuction(null)
+      .accountsPartial({
+        payer: payer.publicKey,
+        seller: seller.publicKey,
+        winner,
+        auction,
+        auctionResult: auctionResultPda(auction),
+        winnerBidEscrow: bidEscrowPda(auction, winner),
+        treasury: treasury.publicKey,
+        nftEscrowAccount: listing.nftEscrow,
+        winnerNftAccount: await tokenAccount(nftMint, winner, listing.tokenProgram),
+        nftMint,
+        tokenProgram: listing.tokenProgram,
+        ...payment,
+        ...overrides,
+      })
+      .remainingAccounts(creatorAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .rpc();
+  }
+
+  /// Refunds `bidder`'s escrow with refund_loser; `overrides` replaces any of the accounts
+  async function refund(listing: Listing, bidder: PublicKey, overrides: object = {}): Promise<void> {
+    const { auction, paymentMint, paymentTokenProgram } = listing;
+    await program.methods
+      .refundLoser()
+      .accountsPartial({
+        bidder,
+        bidEscrow: bidEscrowPda(auction, bidder),
+        auction,
+        auctionResult: auctionResultPda(auction),
+        paymentMint,
+        escrowVault: paymentMint ? escrowVaultPda(auction, bidder) : null,
+        bidderPaymentAccount: paymentMint ? ata(paymentMint, bidder, paymentTokenProgram) : null,
+        paymentTokenProgram: paymentMint ? paymentTokenProgram : null,
+        ...overrides,
+      })
+      .rpc();
+  }
+
+  /// A bidder's refund_losers_batch group: (bidder, bid_escrow), plus
+  /// (bid_vault, bidder token account) for SPL auctions
+  function refundGroup(listing: Listing, bidder: PublicKey): PublicKey[] {
+    const { auction, paymentMint, paymentTokenProgram } = listing;
+    const group = [bidder, bidEscrowPda(auction, bidder)];
+    return paymentMint
+      ? [...group, escrowVaultPda(auction, bidder), ata(paymentMint, bidder, paymentTokenProgram)]
+      : group;
+  }
+
+  async function refundBatch(listing: Listing, groups: PublicKey[][]): Promise<void> {
+    const { auction, paymentMint, paymentTokenProgram } = listing;
+    await program.methods
+      .refundLosersBatch()
+      .accountsPartial({
+        auction,
+        auctionResult: auctionResultPda(auction),
+        paymentMint,
+        paymentTokenProgram: paymentMint ? paymentTokenProgram : null,
+      })
+      .remainingAccounts(groups.flat().map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .rpc();
+  }
+
+  // --- Fixtures ---
+
+  /// Writes the Config PDA directly; the test wallet is its admin
+  async function setConfig(fee: number): Promise<void> {
+    const data = await program.coder.accounts.encode("Config", {
+      admin: payer.publicKey,
+      feeBps: fee,
+      treasury: treasury.publicKey,
+      slashBps,
+      bump: configBump,
+    });
+    setProgramAccount(configPda, data, program.programId);
+  }
+
+  /// Writes a revealed AuctionResult into arcium_program's PDA for `auction`
+  async function setResult(
+    auction: PublicKey,
+    result: {
+      winner: PublicKey;
+      winningAmount: number;
+      secondAmount?: number;
+      defaulters?: PublicKey[];
+    }
+  ): Promise<void> {
+    const [address, bump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_result"), auction.toBuffer()],
+      ARCIUM_PROGRAM_ID
+    );
+    const padded = (keys: PublicKey[], length: number) =>
+      [...keys, ...Array(length - keys.length).fill(PublicKey.default)];
+    const hasWinner = !result.winner.equals(PublicKey.default);
+    const defaulters = result.defaulters ?? [];
+
+    const data = await program.coder.accounts.encode("AuctionResult", {
+      auctionId: auction,
+      bump,
+      revealed: true,
+      winner: result.winner,
+      winningAmount: new anchor.BN(result.winningAmount),
+      revealedAt: new anchor.BN(now),
+      secondAmount: new anchor.BN(result.secondAmount ?? 0),
+      attempts: 1,
+      lastComputationOffset: new anchor.BN(0),
+      winnerCount: hasWinner ? 1 : 0,
+      winners: padded(hasWinner ? [result.winner] : [], 4),
+      defaulterCount: defaulters.length,
+      defaulters: padded(defaulters, 4),
+    });
+    setProgramAccount(address, data, ARCIUM_PROGRAM_ID);
+  }
+
+  /// Writes a Token Metadata account for `mint` with verified `creators`
+  function setMetadata(
+    mint: PublicKey,
+    sellerFeeBasisPoints: number,
+    creators: { address: PublicKey; share: number }[]
+  ): void {
+    const string = (value: string) => {
+      const length = Buffer.alloc(4);
+      length.writeUInt32LE(value.length);
+      return Buffer.concat([length, Buffer.from(value)]);
+    };
+    const u16 = Buffer.alloc(2);
+    u16.writeUInt16LE(sellerFeeBasisPoints);
+    const count = Buffer.alloc(4);
+    count.writeUInt32LE(creators.length);
+
+    const data = Buffer.concat([
+      Buffer.from([4]), // Key::MetadataV1
+      payer.publicKey.toBuffer(),
+      mint.toBuffer(),
+      string("Sealed"),
+      string("SEAL"),
+      string(""),
+      u16,
+      Buffer.from([1]),
+      count,
+      ...creators.map(({ address, share }) => Buffer.concat([address.toBuffer(), Buffer.from([1, share])])),
+      // primary_sale_happened, is_mutable, edition_nonce, token_standard = NonFungible,
+      // collection, uses, collection_details, programmable_config
+      Buffer.from([0, 1, 0, 1, 0, 0, 0, 0, 0]),
+    ]);
+    const [address] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    setProgramAccount(address, data, TOKEN_METADATA_PROGRAM_ID);
+  }
+
+  /// Flags `auction` settled in place, leaving the NFT and every escrow where they are
+  async function markSettled(auction: PublicKey): Promise<void> {
+    const state = await program.account.auction.fetch(auction);
+    const data = Buffer.alloc(svm.getAccount(auction).data.length);
+    (await program.coder.accounts.encode("Auction", { ...state, settled: true })).copy(data);
+    setProgramAccount(auction, data, program.programId);
+  }
+
+  function setProgramAccount(address: PublicKey, data: Buffer, owner: PublicKey): void {
+    svm.setAccount(address, {
+      lamports: Number(svm.minimumBalanceForRentExemption(BigInt(data.length))),
+      data,
+      owner,
+      executable: false,
+    });
+  }
+
+  /// Moves the cluster clock to `unixTimestamp` (and onto a fresh blockhash)
+  function setTime(unixTimestamp: number): void {
+    const clock = svm.getClock();
+    clock.unixTimestamp = BigInt(unixTimestamp);
+    svm.setClock(clock);
+    svm.expireBlockhash();
+    now = unixTimestamp;
+  }
+
+  // --- Accounts and tokens ---
+
+  function fundedKeypair(): Keypair {
+    const keypair = Keypair.generate();
+    svm.airdrop(keypair.publicKey, BigInt(10 * LAMPORTS_PER_SOL));
+    return keypair;
+  }
+
+  async function createMint(
+    decimals: number,
+    authority: PublicKey = payer.publicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+  ): Promise<PublicKey> {
+    const mint = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: payer.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space: MINT_SIZE,
+          lamports: Number(svm.minimumBalanceForRentExemption(BigInt(MINT_SIZE))),
+          programId: tokenProgram,
+        }),
+        createInitializeMint2Instruction(mint.publicKey, decimals, authority, null, tokenProgram)
+      ),
+      [mint]
+    );
+    return mint.publicKey;
+  }
+
+  function ata(mint: PublicKey, owner: PublicKey, tokenProgram: PublicKey = TOKEN_PROGRAM_ID): PublicKey {
+    return getAssociatedTokenAddressSync(mint, owner, true, tokenProgram);
+  }
+
+  /// The ATA of `owner` for `mint`, created if needed
+  async function tokenAccount(
+    mint: PublicKey,
+    owner: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+  ): Promise<PublicKey> {
+    const address = ata(mint, owner, tokenProgram);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createAssociatedTokenAccountIdempotentInstruction(
+          payer.publicKey,
+          address,
+          owner,
+          mint,
+          tokenProgram,
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        )
+      )
+    );
+    return address;
+  }
+
+  async function mintTo(
+    mint: PublicKey,
+    destination: PublicKey,
+    amount: number,
+    authority: Keypair = payer,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+  ): Promise<void> {
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createMintToInstruction(mint, destination, authority.publicKey, amount, [], tokenProgram)
+      ),
+      authority === payer ? [] : [authority]
+    );
+  }
+
+  /// Asserts `promise` fails with the program error `code`
+  async function expectError(promise: Promise<unknown>, code: string): Promise<void> {
+    try {
+      await promise;
+    } catch (err) {
+      const logs: string[] = err.logs ?? err.transactionLogs ?? [];
+      assert.isTrue(
+        err.error?.errorCode?.code === code || logs.some((line) => line.includes(code)),
+        `expected ${code}, got ${err}`
+      );
+      return;
+    }
+    assert.fail(`expected ${code}`);
+  }
+
+  function tokenBalance(address: PublicKey): number {
+    const info = svm.getAccount(address);
+    assert.isNotNull(info, `token account ${address.toBase58()} does not exist`);
+    return Number(unpackAccount(address, { ...info, data: Buffer.from(info.data) }, info.owner).amount);
+  }
+
+  // --- PDAs ---
+
+  function bidEscrowPda(auction: PublicKey, bidder: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("bid_escrow"), auction.toBuffer(), bidder.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function escrowVaultPda(auction: PublicKey, bidder: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("bid_vault"), bidEscrowPda(auction, bidder).toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function auctionResultPda(auction: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_result"), auction.toBuffer()],
+      ARCIUM_PROGRAM_ID
+    )[0];
+  }
+});